@@ -0,0 +1,324 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::key::{
+    DerivationPath, ExtendedKeyError, ExtendedPrivateKey, ExtendedPublicKey, PrivateKey, PublicKey,
+    HARDENED,
+};
+use crate::network::Network;
+
+/// The standard single key account layouts, each with its own address type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// Legacy P2PKH addresses.
+    Bip44,
+    /// Nested segwit P2SH-P2WPKH addresses.
+    Bip49,
+    /// Native segwit P2WPKH addresses.
+    Bip84,
+    /// Taproot P2TR key-path addresses.
+    Bip86,
+}
+
+impl Purpose {
+    pub fn all() -> [Purpose; 4] {
+        [Purpose::Bip44, Purpose::Bip49, Purpose::Bip84, Purpose::Bip86]
+    }
+
+    /// Returns the first child number of the account paths, hardened.
+    pub fn number(&self) -> u32 {
+        match self {
+            Purpose::Bip44 => 44,
+            Purpose::Bip49 => 49,
+            Purpose::Bip84 => 84,
+            Purpose::Bip86 => 86,
+        }
+    }
+
+    /// Returns the purpose implied by a SLIP-132 prefix. xpub and tpub are used by both BIP44
+    /// and BIP86 accounts, and default to BIP44.
+    pub fn from_prefix(prefix: &str) -> Option<Purpose> {
+        match prefix {
+            "xpub" | "xprv" | "tpub" | "tprv" => Some(Purpose::Bip44),
+            "ypub" | "yprv" | "upub" | "uprv" => Some(Purpose::Bip49),
+            "zpub" | "zprv" | "vpub" | "vprv" => Some(Purpose::Bip84),
+            _ => None,
+        }
+    }
+
+    /// Returns the address of a public key, of the type of the purpose.
+    pub fn address(&self, public_key: PublicKey, network: Network) -> Result<String, ExtendedKeyError> {
+        let address = match self {
+            Purpose::Bip44 => public_key.get_address_from_compressed(network),
+            Purpose::Bip49 => public_key.get_p2sh_p2wpkh_address(network)?,
+            Purpose::Bip84 => public_key.get_p2wpkh_address(network)?,
+            Purpose::Bip86 => public_key.get_p2tr_address(network)?,
+        };
+
+        Ok(address)
+    }
+}
+
+impl FromStr for Purpose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim_start_matches("bip") {
+            "44" | "legacy" => Ok(Purpose::Bip44),
+            "49" | "nested-segwit" => Ok(Purpose::Bip49),
+            "84" | "segwit" => Ok(Purpose::Bip84),
+            "86" | "taproot" => Ok(Purpose::Bip86),
+            _ => Err(format!("unknown purpose: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Purpose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let address_type = match self {
+            Purpose::Bip44 => "legacy",
+            Purpose::Bip49 => "nested segwit",
+            Purpose::Bip84 => "native segwit",
+            Purpose::Bip86 => "taproot",
+        };
+
+        write!(f, "BIP{} ({})", self.number(), address_type)
+    }
+}
+
+/// The extended key of an account, private when derived from a seed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountKey {
+    Private(ExtendedPrivateKey),
+    Public(ExtendedPublicKey),
+}
+
+/// An account at `m/purpose'/coin_type'/account'`, coin type being 0 on mainnet and 1 on the
+/// other networks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub purpose: Purpose,
+    pub path: DerivationPath,
+    pub key: AccountKey,
+}
+
+/// A key of the receive or change chain of an account, along with its address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountAddress {
+    pub path: DerivationPath,
+    pub public_key: PublicKey,
+    pub address: String,
+    /// The private key, `None` for accounts derived from an extended public key.
+    pub private_key: Option<PrivateKey>,
+}
+
+impl Account {
+    /// Derives an account from a seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the wallet, usually derived from a mnemonic.
+    /// * `purpose` - The layout of the account.
+    /// * `network` - The network of the addresses, which also sets the coin type.
+    /// * `index` - The index of the account, 0 for the first one.
+    pub fn from_seed(
+        seed: &[u8],
+        purpose: Purpose,
+        network: Network,
+        index: u32,
+    ) -> Result<Self, ExtendedKeyError> {
+        if index >= HARDENED {
+            return Err(ExtendedKeyError::InvalidPath(index.to_string()));
+        }
+
+        let path = account_path(purpose, network, index);
+        let key = ExtendedPrivateKey::from_seed(seed, network)?.derive_path(&path)?;
+
+        Ok(Account { purpose, path, key: AccountKey::Private(key) })
+    }
+
+    /// Returns the account of an account level extended key, e.g. an exported xpub.
+    pub fn from_extended_key(key: AccountKey, purpose: Purpose) -> Result<Self, ExtendedKeyError> {
+        let (network, depth, child_number) = match &key {
+            AccountKey::Private(key) => (key.network, key.depth, key.child_number),
+            AccountKey::Public(key) => (key.network, key.depth, key.child_number),
+        };
+
+        if depth != 3 || child_number < HARDENED {
+            return Err(ExtendedKeyError::NotAnAccountKey);
+        }
+
+        let path = account_path(purpose, network, child_number - HARDENED);
+
+        Ok(Account { purpose, path, key })
+    }
+
+    pub fn network(&self) -> Network {
+        match &self.key {
+            AccountKey::Private(key) => key.network,
+            AccountKey::Public(key) => key.network,
+        }
+    }
+
+    pub fn get_extended_public_key(&self) -> ExtendedPublicKey {
+        match &self.key {
+            AccountKey::Private(key) => key.get_extended_public_key(),
+            AccountKey::Public(key) => key.clone(),
+        }
+    }
+
+    /// Derives the keys and addresses of a range of indexes of the receive or change chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - Whether to derive the change chain (1) rather than the receive one (0).
+    /// * `indexes` - The indexes of the keys within the chain, which cannot be hardened.
+    pub fn derive_addresses(
+        &self,
+        change: bool,
+        indexes: Range<u32>,
+    ) -> Result<Vec<AccountAddress>, ExtendedKeyError> {
+        if !indexes.is_empty() && indexes.end > HARDENED {
+            return Err(ExtendedKeyError::InvalidPath((indexes.end - 1).to_string()));
+        }
+
+        let chain = change as u32;
+        let network = self.network();
+
+        let keys = match &self.key {
+            AccountKey::Private(key) => {
+                let chain_key = key.derive_child(chain)?;
+
+                indexes
+                    .map(|index| {
+                        let child = chain_key.derive_child(index)?;
+                        Ok((index, child.get_public_key(), Some(child.private_key)))
+                    })
+                    .collect::<Result<Vec<_>, ExtendedKeyError>>()?
+            }
+            AccountKey::Public(key) => {
+                let chain_key = key.derive_child(chain)?;
+
+                indexes
+                    .map(|index| Ok((index, chain_key.derive_child(index)?.public_key, None)))
+                    .collect::<Result<Vec<_>, ExtendedKeyError>>()?
+            }
+        };
+
+        keys.into_iter()
+            .map(|(index, public_key, private_key)| {
+                Ok(AccountAddress {
+                    path: self.path.child(chain).child(index),
+                    address: self.purpose.address(public_key.clone(), network)?,
+                    public_key,
+                    private_key,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the path of an account: `m/purpose'/coin_type'/account'`.
+pub fn account_path(purpose: Purpose, network: Network, index: u32) -> DerivationPath {
+    let coin_type = match network {
+        Network::Mainnet => 0,
+        _ => 1,
+    };
+
+    DerivationPath {
+        children: vec![purpose.number() + HARDENED, coin_type + HARDENED, index + HARDENED],
+    }
+}
+
+#[cfg(test)]
+mod account_tests {
+    use super::*;
+    use crate::mnemonic::Mnemonic;
+
+    fn seed() -> Vec<u8> {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+        Mnemonic::from_phrase(phrase).unwrap().to_seed("")
+    }
+
+    fn first_address(purpose: Purpose, network: Network, change: bool) -> AccountAddress {
+        let account = Account::from_seed(&seed(), purpose, network, 0).unwrap();
+
+        account.derive_addresses(change, 0..1).unwrap().remove(0)
+    }
+
+    #[test]
+    fn should_derive_standard_test_vector_addresses() {
+        let vectors = [
+            (Purpose::Bip44, "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA"),
+            (Purpose::Bip49, "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf"),
+            (Purpose::Bip84, "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"),
+            (Purpose::Bip86, "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"),
+        ];
+
+        for (purpose, address) in vectors {
+            assert_eq!(first_address(purpose, Network::Mainnet, false).address, address);
+        }
+    }
+
+    #[test]
+    fn should_derive_change_and_testnet_addresses() {
+        let change = first_address(Purpose::Bip84, Network::Mainnet, true);
+
+        assert_eq!(change.path.to_string(), "m/84'/0'/0'/1/0");
+        assert_eq!(change.address, "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el");
+
+        let testnet = first_address(Purpose::Bip49, Network::Testnet, false);
+
+        assert_eq!(testnet.path.to_string(), "m/49'/1'/0'/0/0");
+        assert_eq!(testnet.address, "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2");
+    }
+
+    #[test]
+    fn should_derive_same_addresses_from_account_xpub() {
+        let account = Account::from_seed(&seed(), Purpose::Bip84, Network::Mainnet, 0).unwrap();
+        let xpub = ExtendedPublicKey::from_str("xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V").unwrap();
+        let watch_only = Account::from_extended_key(AccountKey::Public(xpub), Purpose::Bip84).unwrap();
+
+        let addresses = account.derive_addresses(false, 0..5).unwrap();
+        let watched = watch_only.derive_addresses(false, 0..5).unwrap();
+
+        for (address, watched) in addresses.iter().zip(watched.iter()) {
+            assert_eq!(address.path, watched.path);
+            assert_eq!(address.address, watched.address);
+            assert!(address.private_key.is_some());
+            assert!(watched.private_key.is_none());
+        }
+    }
+
+    #[test]
+    fn should_infer_purpose_from_prefix() {
+        assert_eq!(Purpose::from_prefix("zpub"), Some(Purpose::Bip84));
+        assert_eq!(Purpose::from_prefix("upub"), Some(Purpose::Bip49));
+        assert_eq!(Purpose::from_prefix("xpub"), Some(Purpose::Bip44));
+        assert_eq!(Purpose::from_prefix("Zpub"), None);
+    }
+
+    #[test]
+    fn should_throw_error_if_index_is_hardened() {
+        let account = Account::from_seed(&seed(), Purpose::Bip84, Network::Mainnet, 0).unwrap();
+
+        assert_eq!(account.derive_addresses(false, HARDENED - 1..HARDENED).unwrap().len(), 1);
+        assert_eq!(
+            account.derive_addresses(false, HARDENED - 1..HARDENED + 1),
+            Err(ExtendedKeyError::InvalidPath(HARDENED.to_string()))
+        );
+        assert_eq!(account.derive_addresses(true, HARDENED..HARDENED), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn should_throw_error_if_key_is_not_an_account_key() {
+        let master = ExtendedPrivateKey::from_seed(&seed(), Network::Mainnet).unwrap();
+
+        assert_eq!(
+            Account::from_extended_key(AccountKey::Private(master), Purpose::Bip84),
+            Err(ExtendedKeyError::NotAnAccountKey)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::base58decoder::{base58decode, Base58DecodeError, Payload};
+use crate::bech32::{self, Bech32Error};
+use crate::key::Key;
+use crate::network::Network;
+
+#[derive(Debug, PartialEq)]
+pub enum AddressError {
+    InvalidBase58(Base58DecodeError),
+    InvalidBech32(Bech32Error),
+    UnknownVersion(u8),
+    UnknownHrp(String),
+    InvalidLength(usize),
+}
+
+impl From<Base58DecodeError> for AddressError {
+    fn from(err: Base58DecodeError) -> Self {
+        AddressError::InvalidBase58(err)
+    }
+}
+
+impl From<Bech32Error> for AddressError {
+    fn from(err: Bech32Error) -> Self {
+        AddressError::InvalidBech32(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+    /// A witness program of a version or length without an address type of its own yet.
+    WitnessUnknown,
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AddressType::P2pkh => "P2PKH",
+            AddressType::P2sh => "P2SH",
+            AddressType::P2wpkh => "P2WPKH",
+            AddressType::P2wsh => "P2WSH",
+            AddressType::P2tr => "P2TR",
+            AddressType::WitnessUnknown => "Unknown witness program",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A Base58 or bech32 Bitcoin address.
+///
+/// Testnet and signet share their version bytes and HRP, and Base58 addresses are also shared
+/// with regtest, so `Network::Testnet` stands for all the networks an address is valid on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Address {
+    pub network: Network,
+    pub address_type: AddressType,
+    /// The witness version of segwit addresses, `None` for Base58 addresses.
+    pub witness_version: Option<u8>,
+    /// The hash160 of Base58 addresses, or the witness program of segwit addresses.
+    pub payload: Vec<u8>,
+}
+
+impl Address {
+    /// Returns the P2PKH address of a public key hash.
+    pub fn p2pkh(hash160: Vec<u8>, network: Network) -> Self {
+        Address { network, address_type: AddressType::P2pkh, witness_version: None, payload: hash160 }
+    }
+
+    /// Returns the P2SH address of a script hash.
+    pub fn p2sh(hash160: Vec<u8>, network: Network) -> Self {
+        Address { network, address_type: AddressType::P2sh, witness_version: None, payload: hash160 }
+    }
+
+    /// Returns the segwit address of a witness program.
+    pub fn segwit(version: u8, program: Vec<u8>, network: Network) -> Self {
+        let address_type = match (version, program.len()) {
+            (0, 20) => AddressType::P2wpkh,
+            (0, 32) => AddressType::P2wsh,
+            (1, 32) => AddressType::P2tr,
+            _ => AddressType::WitnessUnknown,
+        };
+
+        Address { network, address_type, witness_version: Some(version), payload: program }
+    }
+
+    /// Returns the scriptPubKey the address stands for.
+    pub fn script_pubkey(&self) -> Vec<u8> {
+        let mut script = Vec::new();
+
+        match self.witness_version {
+            None if self.address_type == AddressType::P2pkh => {
+                script.extend_from_slice(&[0x76, 0xa9, 0x14]);
+                script.extend_from_slice(&self.payload);
+                script.extend_from_slice(&[0x88, 0xac]);
+            }
+            None => {
+                script.extend_from_slice(&[0xa9, 0x14]);
+                script.extend_from_slice(&self.payload);
+                script.push(0x87);
+            }
+            Some(version) => {
+                script.push(if version == 0 { 0x00 } else { 0x50 + version });
+                script.push(self.payload.len() as u8);
+                script.extend_from_slice(&self.payload);
+            }
+        }
+
+        script
+    }
+
+    /// Returns the address of a standard scriptPubKey, `None` for scripts without one such as
+    /// bare multisig or OP_RETURN outputs.
+    pub fn from_script_pubkey(script: &[u8], network: Network) -> Option<Self> {
+        match script {
+            [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => {
+                Some(Address::p2pkh(hash.to_vec(), network))
+            }
+            [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => Some(Address::p2sh(hash.to_vec(), network)),
+            [version @ (0x00 | 0x51..=0x60), length, program @ ..]
+                if *length as usize == program.len() && (2..=40).contains(&program.len()) =>
+            {
+                let version = if *version == 0x00 { 0 } else { version - 0x50 };
+
+                if version == 0 && program.len() != 20 && program.len() != 32 {
+                    return None;
+                }
+
+                Some(Address::segwit(version, program.to_vec(), network))
+            }
+            _ => None,
+        }
+    }
+
+    fn from_base58(address: &str) -> Result<Self, AddressError> {
+        let decoded = base58decode(address)?;
+
+        match decoded.identify() {
+            Payload::P2pkh { network, hash160 } => Ok(Address::p2pkh(hash160, network)),
+            Payload::P2sh { network, hash160 } => Ok(Address::p2sh(hash160, network)),
+            _ if decoded.payload.len() == 20 => Err(AddressError::UnknownVersion(decoded.version)),
+            _ => Err(AddressError::InvalidLength(decoded.payload.len())),
+        }
+    }
+
+    fn from_bech32(address: &str) -> Result<Self, AddressError> {
+        let (hrp, version, program) = bech32::decode_segwit_address(address)?;
+
+        match Network::all().into_iter().find(|network| network.hrp() == hrp) {
+            Some(network) => Ok(Address::segwit(version, program, network)),
+            None => Err(AddressError::UnknownHrp(hrp)),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    /// Parses a Base58 or bech32 address, verifying its checksum and version bytes or HRP.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hrp = s.rfind('1').map(|separator| s[..separator].to_lowercase());
+
+        if let Some(hrp) = hrp {
+            if Network::all().iter().any(|network| network.hrp() == hrp) {
+                return Address::from_bech32(s);
+            }
+        }
+
+        match Address::from_base58(s) {
+            Err(AddressError::InvalidBase58(Base58DecodeError::InvalidBase58(_)))
+                if bech32::decode(s).is_ok() =>
+            {
+                Address::from_bech32(s)
+            }
+            r => r,
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.witness_version {
+            Some(version) => {
+                let address = bech32::encode_segwit_address(self.network.hrp(), version, &self.payload)
+                    .map_err(|_| fmt::Error)?;
+
+                write!(f, "{}", address)
+            }
+            None => {
+                let version = match self.address_type {
+                    AddressType::P2pkh => self.network.p2pkh_version(),
+                    _ => self.network.p2sh_version(),
+                };
+
+                let mut data = self.payload.clone();
+                data.insert(0, version);
+                data.append_checksum();
+
+                write!(f, "{}", bs58::encode(data).into_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod address_tests {
+    use super::*;
+    use crate::key::{ADDRESS_FROM_COMPRESSED, P2SH_P2WPKH_ADDRESS, P2TR_ADDRESS, P2WPKH_ADDRESS};
+
+    fn script_pubkey(address: &str) -> String {
+        hex::encode(Address::from_str(address).unwrap().script_pubkey())
+    }
+
+    #[test]
+    fn should_parse_p2pkh_address() {
+        let address = Address::from_str(ADDRESS_FROM_COMPRESSED).unwrap();
+
+        assert_eq!(address.network, Network::Mainnet);
+        assert_eq!(address.address_type, AddressType::P2pkh);
+        assert_eq!(address.witness_version, None);
+        assert_eq!(
+            script_pubkey(ADDRESS_FROM_COMPRESSED),
+            "76a914bbc1e42a39d05a4cc61752d6963b7f69d09bb27b88ac"
+        );
+    }
+
+    #[test]
+    fn should_parse_p2sh_address() {
+        let address = Address::from_str("2N7XQ9yUwZxXP7WgkFMtbz9jDs2Kr2njYRy").unwrap();
+
+        assert_eq!(address.network, Network::Testnet);
+        assert_eq!(address.address_type, AddressType::P2sh);
+        assert_eq!(
+            script_pubkey(P2SH_P2WPKH_ADDRESS),
+            "a9149ca0e746160117cc342531ab7e44d0e12961c54487"
+        );
+    }
+
+    #[test]
+    fn should_parse_segwit_addresses() {
+        let address = Address::from_str(P2WPKH_ADDRESS).unwrap();
+        assert_eq!(address.address_type, AddressType::P2wpkh);
+        assert_eq!(address.witness_version, Some(0));
+        assert_eq!(script_pubkey(P2WPKH_ADDRESS), "0014bbc1e42a39d05a4cc61752d6963b7f69d09bb27b");
+
+        let address = Address::from_str(P2TR_ADDRESS).unwrap();
+        assert_eq!(address.address_type, AddressType::P2tr);
+        assert_eq!(
+            script_pubkey(P2TR_ADDRESS),
+            "51208632f065149f924f7f78bee082cc19f81e5227f7526127a98a97db924e1a102c"
+        );
+
+        let address = Address::from_str("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7").unwrap();
+        assert_eq!(address.network, Network::Testnet);
+        assert_eq!(address.address_type, AddressType::P2wsh);
+
+        let address = Address::from_str("bcrt1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmnxyz5v").unwrap();
+        assert_eq!(address.network, Network::Regtest);
+    }
+
+    #[test]
+    fn should_parse_unknown_witness_programs() {
+        let address = Address::from_str("BC1SW50QGDZ25J").unwrap();
+
+        assert_eq!(address.address_type, AddressType::WitnessUnknown);
+        assert_eq!(address.witness_version, Some(16));
+        assert_eq!(hex::encode(address.script_pubkey()), "6002751e");
+    }
+
+    #[test]
+    fn should_reencode_parsed_addresses() {
+        for address in [ADDRESS_FROM_COMPRESSED, P2SH_P2WPKH_ADDRESS, P2WPKH_ADDRESS, P2TR_ADDRESS] {
+            assert_eq!(Address::from_str(address).unwrap().to_string(), address);
+        }
+    }
+
+    #[test]
+    fn should_return_address_of_script_pubkey() {
+        for address in [ADDRESS_FROM_COMPRESSED, P2SH_P2WPKH_ADDRESS, P2WPKH_ADDRESS, P2TR_ADDRESS] {
+            let script = Address::from_str(address).unwrap().script_pubkey();
+
+            assert_eq!(
+                Address::from_script_pubkey(&script, Network::Mainnet).unwrap().to_string(),
+                address
+            );
+        }
+
+        assert_eq!(Address::from_script_pubkey(&hex::decode("6a04deadbeef").unwrap(), Network::Mainnet), None);
+        assert_eq!(Address::from_script_pubkey(&hex::decode("0003000102").unwrap(), Network::Mainnet), None);
+    }
+
+    #[test]
+    fn should_throw_error_if_address_is_invalid() {
+        assert_eq!(
+            Address::from_str("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZz"),
+            Err(AddressError::InvalidBase58(Base58DecodeError::InvalidChecksum {
+                expected: vec![0x37, 0xfe, 0xfc, 0xd0],
+                found: vec![0x37, 0xfe, 0xfc, 0xd1],
+            }))
+        );
+        assert_eq!(
+            Address::from_str("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtJ"),
+            Err(AddressError::InvalidLength(33))
+        );
+        assert_eq!(
+            Address::from_str("bc1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmmfxucc"),
+            Err(AddressError::InvalidBech32(Bech32Error::InvalidChecksum))
+        );
+        assert_eq!(
+            Address::from_str("ltc1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnml4ucqx"),
+            Err(AddressError::UnknownHrp("ltc".to_string()))
+        );
+    }
+}
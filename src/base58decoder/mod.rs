@@ -1,11 +1,74 @@
+use crate::key::Key;
+
+mod payload;
+pub use payload::{Payload, EXTENDED_KEY_VERSIONS};
+
+#[derive(Debug, PartialEq)]
+pub enum Base58DecodeError {
+    InvalidBase58(bs58::decode::Error),
+    TooShort(usize),
+    InvalidChecksum { expected: Vec<u8>, found: Vec<u8> },
+}
+
+impl From<bs58::decode::Error> for Base58DecodeError {
+    fn from(err: bs58::decode::Error) -> Self {
+        Base58DecodeError::InvalidBase58(err)
+    }
+}
+
 /// Represents a decoded base58check string
+#[derive(Debug, PartialEq)]
+pub struct Decoded {
+    /// The first byte of the decoded data.
+    pub version: u8,
+    /// The bytes between the version and the checksum.
+    pub payload: Vec<u8>,
+    /// The last four bytes of the decoded data.
+    pub checksum: Vec<u8>,
+}
+
+impl Decoded {
+    /// Returns the version followed by the payload, i.e. the data the checksum commits to.
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = vec![self.version];
+        data.extend_from_slice(&self.payload);
+
+        data
+    }
+
+    /// Returns the first four bytes of the double SHA256 of the version and payload.
+    pub fn expected_checksum(&self) -> Vec<u8> {
+        let mut data = self.data();
+        let length = data.len();
+
+        data.append_checksum();
+        data.split_off(length)
+    }
+
+    /// Returns whether the decoded checksum matches the one computed from the data.
+    pub fn has_valid_checksum(&self) -> bool {
+        self.expected_checksum() == self.checksum
+    }
+
+    /// Returns what the version bytes and length say this payload is.
+    pub fn identify(&self) -> Payload {
+        Payload::identify(self)
+    }
+}
+
+/// Encodes data as base58check, appending the checksum before encoding it
 ///
-/// The first value is the version;
-/// The second value is the payload;
-/// The third value is the checksum;
-type Decoded = (String, String, String);
+/// # Arguments
+///
+/// * `data`: The version bytes followed by the payload
+pub fn base58encode(data: &[u8]) -> String {
+    let mut data = data.to_vec();
+    data.append_checksum();
+
+    bs58::encode(data).into_string()
+}
 
-/// Decodes a base58check encoded string slice
+/// Decodes a base58check encoded string slice, verifying its checksum
 ///
 /// # Arguments
 ///
@@ -13,52 +76,119 @@ type Decoded = (String, String, String);
 ///
 /// # Return
 ///
-/// * A `Decoded` type
-pub fn base58decode(input: &str) -> Result<Decoded, bs58::decode::Error> {
-    let decoded = bs58::decode(input).into_vec()?;
+/// * A `Decoded` struct, or an error if the input is not base58, is too short to hold a
+///   version and a checksum, or its checksum does not match.
+pub fn base58decode(input: &str) -> Result<Decoded, Base58DecodeError> {
+    let decoded = base58decode_unchecked(input)?;
+
+    match decoded.has_valid_checksum() {
+        true => Ok(decoded),
+        false => Err(Base58DecodeError::InvalidChecksum {
+            expected: decoded.expected_checksum(),
+            found: decoded.checksum,
+        }),
+    }
+}
+
+/// Decodes a base58check encoded string slice without verifying its checksum
+///
+/// # Arguments
+///
+/// * `input`: A base58check enconded string slice
+pub fn base58decode_unchecked(input: &str) -> Result<Decoded, Base58DecodeError> {
+    let mut decoded = bs58::decode(input).into_vec()?;
+
+    if decoded.len() < 5 {
+        return Err(Base58DecodeError::TooShort(decoded.len()));
+    }
 
-    let version = hex::encode(vec![decoded[0]]);
-    let payload = hex::encode(&decoded[1..decoded.len() - 4]);
-    let checksum = hex::encode(&decoded[decoded.len() - 4..decoded.len()]);
+    let checksum = decoded.split_off(decoded.len() - 4);
+    let payload = decoded.split_off(1);
 
-    Ok((version, payload, checksum))
+    Ok(Decoded {
+        version: decoded[0],
+        payload,
+        checksum,
+    })
 }
 
 #[cfg(test)]
 mod base58decoder_tests {
-    use super::base58decode;
+    use super::{base58decode, base58decode_unchecked, base58encode, Base58DecodeError};
 
     #[test]
     fn test_with_wif_private_key_format() {
-        let (version, payload, checksum) =
-            base58decode("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jcn").unwrap();
+        let decoded = base58decode("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jcn").unwrap();
 
-        assert_eq!(version, "80");
+        assert_eq!(decoded.version, 0x80);
         assert_eq!(
-            payload,
+            hex::encode(decoded.payload),
             "1e99423a4ed27608a15a2616a2b0e9e52ced330ac530edcc32c8ffc6a526aedd"
         );
-        assert_eq!(checksum, "c47e83ff");
+        assert_eq!(hex::encode(decoded.checksum), "c47e83ff");
     }
 
     #[test]
     fn test_with_wif_compressed_private_key_format() {
-        let (version, payload, checksum) =
-            base58decode("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtJ").unwrap();
+        let decoded = base58decode("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtJ").unwrap();
 
-        assert_eq!(version, "80");
+        assert_eq!(decoded.version, 0x80);
         assert_eq!(
-            payload,
+            hex::encode(decoded.payload),
             "1e99423a4ed27608a15a2616a2b0e9e52ced330ac530edcc32c8ffc6a526aedd01"
         );
-        assert_eq!(checksum, "7695738b");
+        assert_eq!(hex::encode(decoded.checksum), "7695738b");
     }
 
     #[test]
     fn should_throw_error_if_invalid_base58_char() {
         assert_eq!(
             base58decode("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtl"),
-            Err(bs58::decode::Error::InvalidCharacter { character: 'l', index: 51 }),
+            Err(Base58DecodeError::InvalidBase58(
+                bs58::decode::Error::InvalidCharacter { character: 'l', index: 51 }
+            )),
         )
     }
+
+    #[test]
+    fn should_throw_error_if_checksum_does_not_match() {
+        assert_eq!(
+            base58decode("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jco"),
+            Err(Base58DecodeError::InvalidChecksum {
+                expected: vec![0xc4, 0x7e, 0x83, 0xff],
+                found: vec![0xc4, 0x7e, 0x84, 0x00],
+            }),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_input_is_too_short() {
+        assert_eq!(base58decode(""), Err(Base58DecodeError::TooShort(0)));
+        assert_eq!(base58decode("2g"), Err(Base58DecodeError::TooShort(1)));
+        assert_eq!(base58decode("2VfUX"), Err(Base58DecodeError::TooShort(4)));
+    }
+
+    #[test]
+    fn should_decode_without_verifying_checksum() {
+        let decoded =
+            base58decode_unchecked("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jco").unwrap();
+
+        assert_eq!(decoded.version, 0x80);
+        assert!(!decoded.has_valid_checksum());
+    }
+
+    #[test]
+    fn should_encode_decoded_data_back() {
+        let wif = "KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtJ";
+
+        assert_eq!(base58encode(&base58decode(wif).unwrap().data()), wif);
+    }
+
+    #[test]
+    fn should_decode_empty_payload() {
+        let decoded = base58decode("1Wh4bh").unwrap();
+
+        assert_eq!(decoded.version, 0x00);
+        assert!(decoded.payload.is_empty());
+    }
 }
@@ -0,0 +1,232 @@
+use std::fmt;
+
+use crate::base58decoder::Decoded;
+use crate::network::Network;
+
+/// Prefix, version bytes, network and whether the key is private, for every known extended key
+/// serialization.
+pub const EXTENDED_KEY_VERSIONS: [(&str, [u8; 4], Network, bool); 20] = [
+    ("xpub", [0x04, 0x88, 0xb2, 0x1e], Network::Mainnet, false),
+    ("xprv", [0x04, 0x88, 0xad, 0xe4], Network::Mainnet, true),
+    ("ypub", [0x04, 0x9d, 0x7c, 0xb2], Network::Mainnet, false),
+    ("yprv", [0x04, 0x9d, 0x78, 0x78], Network::Mainnet, true),
+    ("zpub", [0x04, 0xb2, 0x47, 0x46], Network::Mainnet, false),
+    ("zprv", [0x04, 0xb2, 0x43, 0x0c], Network::Mainnet, true),
+    ("Ypub", [0x02, 0x95, 0xb4, 0x3f], Network::Mainnet, false),
+    ("Yprv", [0x02, 0x95, 0xb0, 0x05], Network::Mainnet, true),
+    ("Zpub", [0x02, 0xaa, 0x7e, 0xd3], Network::Mainnet, false),
+    ("Zprv", [0x02, 0xaa, 0x7a, 0x99], Network::Mainnet, true),
+    ("tpub", [0x04, 0x35, 0x87, 0xcf], Network::Testnet, false),
+    ("tprv", [0x04, 0x35, 0x83, 0x94], Network::Testnet, true),
+    ("upub", [0x04, 0x4a, 0x52, 0x62], Network::Testnet, false),
+    ("uprv", [0x04, 0x4a, 0x4e, 0x28], Network::Testnet, true),
+    ("vpub", [0x04, 0x5f, 0x1c, 0xf6], Network::Testnet, false),
+    ("vprv", [0x04, 0x5f, 0x18, 0xbc], Network::Testnet, true),
+    ("Upub", [0x02, 0x42, 0x89, 0xef], Network::Testnet, false),
+    ("Uprv", [0x02, 0x42, 0x85, 0xb5], Network::Testnet, true),
+    ("Vpub", [0x02, 0x57, 0x54, 0x83], Network::Testnet, false),
+    ("Vprv", [0x02, 0x57, 0x50, 0x48], Network::Testnet, true),
+];
+
+/// What a Base58Check payload represents, given its version bytes and length.
+///
+/// Testnet, signet and regtest share their version bytes, so `Network::Testnet` stands for
+/// all three.
+#[derive(Debug, PartialEq)]
+pub enum Payload {
+    P2pkh {
+        network: Network,
+        hash160: Vec<u8>,
+    },
+    P2sh {
+        network: Network,
+        hash160: Vec<u8>,
+    },
+    Wif {
+        network: Network,
+        compressed: bool,
+        key: Vec<u8>,
+    },
+    ExtendedKey {
+        prefix: &'static str,
+        network: Network,
+        private: bool,
+        depth: u8,
+        fingerprint: Vec<u8>,
+        child_number: u32,
+        chain_code: Vec<u8>,
+        key: Vec<u8>,
+    },
+    Unknown,
+}
+
+impl Payload {
+    /// Identifies the payload of a decoded Base58Check string.
+    pub fn identify(decoded: &Decoded) -> Self {
+        let data = decoded.data();
+        let version = decoded.version;
+        let payload = &decoded.payload;
+
+        match data.len() {
+            21 => {
+                if let Some(network) = find_network(|n| n.p2pkh_version() == version) {
+                    return Payload::P2pkh { network, hash160: payload.to_vec() };
+                }
+                if let Some(network) = find_network(|n| n.p2sh_version() == version) {
+                    return Payload::P2sh { network, hash160: payload.to_vec() };
+                }
+            }
+            33 | 34 => {
+                let compressed = data.len() == 34;
+
+                if compressed && payload[32] != 0x01 {
+                    return Payload::Unknown;
+                }
+                if let Some(network) = Network::from_wif_version(version) {
+                    return Payload::Wif { network, compressed, key: payload[..32].to_vec() };
+                }
+            }
+            78 => {
+                let found = EXTENDED_KEY_VERSIONS
+                    .iter()
+                    .find(|(_, bytes, _, _)| bytes[..] == data[..4]);
+
+                if let Some((prefix, _, network, private)) = found {
+                    let mut child_number = [0x00; 4];
+                    child_number.copy_from_slice(&data[9..13]);
+
+                    return Payload::ExtendedKey {
+                        prefix,
+                        network: *network,
+                        private: *private,
+                        depth: data[4],
+                        fingerprint: data[5..9].to_vec(),
+                        child_number: u32::from_be_bytes(child_number),
+                        chain_code: data[13..45].to_vec(),
+                        key: data[45..].to_vec(),
+                    };
+                }
+            }
+            _ => (),
+        }
+
+        Payload::Unknown
+    }
+}
+
+fn find_network(predicate: impl Fn(&Network) -> bool) -> Option<Network> {
+    Network::all().into_iter().find(predicate)
+}
+
+fn network_name(network: &Network) -> &'static str {
+    match network {
+        Network::Mainnet => "mainnet",
+        _ => "testnet/signet/regtest",
+    }
+}
+
+impl fmt::Display for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Payload::P2pkh { network, .. } => {
+                write!(f, "P2PKH address ({})", network_name(network))
+            }
+            Payload::P2sh { network, .. } => {
+                write!(f, "P2SH address ({})", network_name(network))
+            }
+            Payload::Wif { network, compressed, .. } => {
+                let compression = if *compressed { "compressed" } else { "uncompressed" };
+
+                write!(f, "WIF private key, {} ({})", compression, network_name(network))
+            }
+            Payload::ExtendedKey { prefix, network, private, .. } => {
+                let visibility = if *private { "private" } else { "public" };
+
+                write!(
+                    f,
+                    "BIP32 extended {} key, {} ({})",
+                    visibility,
+                    prefix,
+                    network_name(network)
+                )
+            }
+            Payload::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod payload_tests {
+    use super::Payload;
+    use crate::base58decoder::base58decode;
+    use crate::key::{ADDRESS_FROM_COMPRESSED, COMPRESSED_WIF, PRIVATE_KEY, TESTNET_WIF};
+    use crate::network::Network;
+
+    fn identify(input: &str) -> Payload {
+        Payload::identify(&base58decode(input).unwrap())
+    }
+
+    #[test]
+    fn should_identify_p2pkh_address() {
+        assert_eq!(
+            identify(ADDRESS_FROM_COMPRESSED),
+            Payload::P2pkh {
+                network: Network::Mainnet,
+                hash160: hex::decode("bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap(),
+            }
+        )
+    }
+
+    #[test]
+    fn should_identify_p2sh_address() {
+        let hash160 = hex::decode("bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap();
+
+        assert_eq!(
+            identify("3JonZDaJ9KHrNQ4ym3hjw7sFGCA4RPXfWv"),
+            Payload::P2sh { network: Network::Mainnet, hash160: hash160.clone() }
+        );
+        assert_eq!(
+            identify("2NAMzcxWKkmoCaBhXSBKcZ4rWUYNEEEYRLx"),
+            Payload::P2sh { network: Network::Testnet, hash160 }
+        );
+    }
+
+    #[test]
+    fn should_identify_wif() {
+        let key = hex::decode(PRIVATE_KEY).unwrap();
+
+        assert_eq!(
+            identify(COMPRESSED_WIF),
+            Payload::Wif { network: Network::Mainnet, compressed: true, key: key.clone() }
+        );
+        assert_eq!(
+            identify(TESTNET_WIF),
+            Payload::Wif { network: Network::Testnet, compressed: false, key }
+        );
+    }
+
+    #[test]
+    fn should_identify_extended_public_key() {
+        let payload = identify("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw");
+
+        assert_eq!(
+            payload,
+            Payload::ExtendedKey {
+                prefix: "xpub",
+                network: Network::Mainnet,
+                private: false,
+                depth: 1,
+                fingerprint: hex::decode("3442193e").unwrap(),
+                child_number: 0x80000000,
+                chain_code: hex::decode("47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141").unwrap(),
+                key: hex::decode("035a784662a4a20a65bf6aab9ae98a6c068a81c52e4b032c0fb5400c706cfccc56").unwrap(),
+            }
+        );
+        assert_eq!(payload.to_string(), "BIP32 extended public key, xpub (mainnet)");
+    }
+
+    #[test]
+    fn should_return_unknown_for_unrecognized_payloads() {
+        assert_eq!(identify("1Wh4bh"), Payload::Unknown);
+    }
+}
@@ -0,0 +1,426 @@
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const MAX_LENGTH: usize = 90;
+const CHECKSUM_LENGTH: usize = 6;
+
+/// The checksum constants of BIP173 (bech32) and BIP350 (bech32m).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(&self) -> u32 {
+        match self {
+            Variant::Bech32 => 0x01,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+
+    fn from_constant(constant: u32) -> Option<Variant> {
+        match constant {
+            0x01 => Some(Variant::Bech32),
+            0x2bc830a3 => Some(Variant::Bech32m),
+            _ => None,
+        }
+    }
+
+    /// Returns the variant segwit addresses of the given witness version must use.
+    pub fn for_witness_version(version: u8) -> Variant {
+        match version {
+            0 => Variant::Bech32,
+            _ => Variant::Bech32m,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Bech32Error {
+    MixedCase,
+    MissingSeparator,
+    InvalidLength(usize),
+    InvalidHrp,
+    InvalidCharacter(char),
+    InvalidChecksum,
+    InvalidData(u8),
+    InvalidPadding,
+    InvalidWitnessVersion(u8),
+    InvalidProgramLength(usize),
+    InvalidVariant(Variant),
+}
+
+/// Represents a decoded bech32 string
+///
+/// The first value is the human-readable part;
+/// The second value is the data part as 5-bit values, without the checksum;
+/// The third value is the checksum variant;
+pub type Decoded = (String, Vec<u8>, Variant);
+
+/// Represents a decoded segwit address
+///
+/// The first value is the human-readable part;
+/// The second value is the witness version;
+/// The third value is the witness program;
+pub type DecodedSegwit = (String, u8, Vec<u8>);
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut checksum: u32 = 1;
+
+    for value in values {
+        let top = checksum >> 25;
+        checksum = (checksum & 0x1ffffff) << 5 ^ *value as u32;
+
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+
+    checksum
+}
+
+fn expand_hrp(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|b| b & 0x1f));
+
+    expanded
+}
+
+fn create_checksum(hrp: &str, data: &[u8], variant: Variant) -> Vec<u8> {
+    let mut values = expand_hrp(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0x00; CHECKSUM_LENGTH]);
+
+    let checksum = polymod(&values) ^ variant.constant();
+
+    (0..CHECKSUM_LENGTH)
+        .map(|i| ((checksum >> (5 * (5 - i))) & 0x1f) as u8)
+        .collect()
+}
+
+fn validate_hrp(hrp: &str) -> Result<(), Bech32Error> {
+    let valid = !hrp.is_empty() && hrp.len() <= 83 && hrp.bytes().all(|b| (33..=126).contains(&b));
+
+    match valid {
+        true => Ok(()),
+        false => Err(Bech32Error::InvalidHrp),
+    }
+}
+
+/// Regroups a slice of `from`-bit values into `to`-bit values.
+///
+/// # Arguments
+///
+/// * `data`: values of at most `from` bits each
+/// * `from`: the width of the input values
+/// * `to`: the width of the output values
+/// * `pad`: whether incomplete trailing groups are padded with zeros or rejected
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut accumulator: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut converted = Vec::new();
+    let max_value = (1 << to) - 1;
+    let max_accumulator = (1 << (from + to - 1)) - 1;
+
+    for value in data {
+        if (*value as u32) >> from != 0 {
+            return Err(Bech32Error::InvalidData(*value));
+        }
+
+        accumulator = ((accumulator << from) | *value as u32) & max_accumulator;
+        bits += from;
+
+        while bits >= to {
+            bits -= to;
+            converted.push(((accumulator >> bits) & max_value) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            converted.push(((accumulator << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((accumulator << (to - bits)) & max_value) != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+
+    Ok(converted)
+}
+
+/// Encodes 5-bit values as a bech32 or bech32m string
+///
+/// # Arguments
+///
+/// * `hrp`: the human-readable part
+/// * `data`: the data part as 5-bit values
+/// * `variant`: which checksum constant to use
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Bech32Error> {
+    validate_hrp(hrp)?;
+
+    if hrp.len() + 1 + data.len() + CHECKSUM_LENGTH > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength(hrp.len() + 1 + data.len() + CHECKSUM_LENGTH));
+    }
+
+    let hrp = hrp.to_lowercase();
+    let mut encoded = format!("{}1", hrp);
+
+    for value in data.iter().chain(create_checksum(&hrp, data, variant).iter()) {
+        match CHARSET.get(*value as usize) {
+            Some(c) => encoded.push(*c as char),
+            None => return Err(Bech32Error::InvalidData(*value)),
+        }
+    }
+
+    Ok(encoded)
+}
+
+/// Decodes a bech32 or bech32m string, verifying its checksum
+///
+/// # Arguments
+///
+/// * `input`: a bech32 or bech32m encoded string slice
+///
+/// # Return
+///
+/// * A `Decoded` type
+pub fn decode(input: &str) -> Result<Decoded, Bech32Error> {
+    if input.len() > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength(input.len()));
+    }
+
+    if input.to_lowercase() != input && input.to_uppercase() != input {
+        return Err(Bech32Error::MixedCase);
+    }
+
+    let input = input.to_lowercase();
+    let separator = input.rfind('1').ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, data) = (&input[..separator], &input[separator + 1..]);
+
+    validate_hrp(hrp)?;
+
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(Bech32Error::InvalidLength(input.len()));
+    }
+
+    let data = data
+        .chars()
+        .map(|c| match CHARSET.iter().position(|x| *x as char == c) {
+            Some(position) => Ok(position as u8),
+            None => Err(Bech32Error::InvalidCharacter(c)),
+        })
+        .collect::<Result<Vec<u8>, Bech32Error>>()?;
+
+    let mut values = expand_hrp(hrp);
+    values.extend_from_slice(&data);
+
+    match Variant::from_constant(polymod(&values)) {
+        Some(variant) => Ok((
+            hrp.to_string(),
+            data[..data.len() - CHECKSUM_LENGTH].to_vec(),
+            variant,
+        )),
+        None => Err(Bech32Error::InvalidChecksum),
+    }
+}
+
+/// Encodes a witness program as a segwit address, choosing the variant from the version
+///
+/// # Arguments
+///
+/// * `hrp`: the human-readable part, e.g. `bc`
+/// * `version`: the witness version, from 0 to 16
+/// * `program`: the witness program
+pub fn encode_segwit_address(hrp: &str, version: u8, program: &[u8]) -> Result<String, Bech32Error> {
+    validate_witness_program(version, program)?;
+
+    let mut data = vec![version];
+    data.append(&mut convert_bits(program, 8, 5, true)?);
+
+    encode(hrp, &data, Variant::for_witness_version(version))
+}
+
+/// Decodes a segwit address, enforcing the witness version and program length rules
+///
+/// # Arguments
+///
+/// * `address`: a bech32 or bech32m encoded segwit address
+///
+/// # Return
+///
+/// * A `DecodedSegwit` type
+pub fn decode_segwit_address(address: &str) -> Result<DecodedSegwit, Bech32Error> {
+    let (hrp, data, variant) = decode(address)?;
+
+    if data.is_empty() {
+        return Err(Bech32Error::InvalidLength(address.len()));
+    }
+
+    let version = data[0];
+
+    if version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion(version));
+    }
+
+    if variant != Variant::for_witness_version(version) {
+        return Err(Bech32Error::InvalidVariant(variant));
+    }
+
+    let program = convert_bits(&data[1..], 5, 8, false)?;
+
+    validate_witness_program(version, &program)?;
+
+    Ok((hrp, version, program))
+}
+
+fn validate_witness_program(version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion(version));
+    }
+
+    if program.len() < 2 || program.len() > 40 || (version == 0 && program.len() != 20 && program.len() != 32) {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod bech32_tests {
+    use super::*;
+
+    #[test]
+    fn should_decode_valid_bech32_strings() {
+        let valid = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+
+        for input in valid {
+            let (_, _, variant) = decode(input).unwrap();
+            assert_eq!(variant, Variant::Bech32, "{}", input);
+        }
+    }
+
+    #[test]
+    fn should_decode_valid_bech32m_strings() {
+        let valid = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+
+        for input in valid {
+            let (_, _, variant) = decode(input).unwrap();
+            assert_eq!(variant, Variant::Bech32m, "{}", input);
+        }
+    }
+
+    #[test]
+    fn should_reencode_decoded_strings() {
+        let (hrp, data, variant) = decode("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx").unwrap();
+
+        assert_eq!(
+            encode(&hrp, &data, variant).unwrap(),
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx"
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_string_is_invalid() {
+        assert_eq!(decode("pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+        assert_eq!(decode("1pzry9x0s0muk"), Err(Bech32Error::InvalidHrp));
+        assert_eq!(decode("x1b4n0q5v"), Err(Bech32Error::InvalidCharacter('b')));
+        assert_eq!(decode("li1dgmt3"), Err(Bech32Error::InvalidLength(8)));
+        assert_eq!(decode("A1G7SGD8"), Err(Bech32Error::InvalidChecksum));
+        assert_eq!(decode("a12UEL5L"), Err(Bech32Error::MixedCase));
+        assert_eq!(
+            decode("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx"),
+            Err(Bech32Error::InvalidLength(91))
+        );
+    }
+
+    #[test]
+    fn should_decode_segwit_addresses() {
+        assert_eq!(
+            decode_segwit_address("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4").unwrap(),
+            (
+                "bc".to_string(),
+                0,
+                hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap()
+            )
+        );
+        assert_eq!(
+            decode_segwit_address("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7").unwrap(),
+            (
+                "tb".to_string(),
+                0,
+                hex::decode("1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262").unwrap()
+            )
+        );
+        assert_eq!(
+            decode_segwit_address("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y").unwrap(),
+            (
+                "bc".to_string(),
+                1,
+                hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6").unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_segwit_address_is_invalid() {
+        assert_eq!(
+            decode_segwit_address("bc1zw508d6qejxtdg4y5r3zarvaryvqyzf3du"),
+            Err(Bech32Error::InvalidVariant(Variant::Bech32))
+        );
+        assert_eq!(
+            decode_segwit_address("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"),
+            Err(Bech32Error::InvalidVariant(Variant::Bech32))
+        );
+        assert_eq!(
+            decode_segwit_address("BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P"),
+            Err(Bech32Error::InvalidProgramLength(16))
+        );
+        assert_eq!(
+            decode_segwit_address("bc1gmk9yu"),
+            Err(Bech32Error::InvalidLength(9))
+        );
+    }
+
+    #[test]
+    fn should_encode_segwit_addresses() {
+        let program = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+
+        assert_eq!(
+            encode_segwit_address("bc", 0, &program).unwrap(),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            encode_segwit_address("bc", 17, &program),
+            Err(Bech32Error::InvalidWitnessVersion(17))
+        );
+        assert_eq!(
+            encode_segwit_address("bc", 0, &program[..19]),
+            Err(Bech32Error::InvalidProgramLength(19))
+        );
+    }
+
+    #[test]
+    fn should_convert_bits_back_and_forth() {
+        let data = hex::decode("68656c6c6f").unwrap();
+        let converted = convert_bits(&data, 8, 5, true).unwrap();
+
+        assert_eq!(convert_bits(&converted, 5, 8, false).unwrap(), data);
+        assert_eq!(convert_bits(&[0x20], 5, 8, true), Err(Bech32Error::InvalidData(0x20)));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::descriptor::DescriptorError;
+
+/// Characters allowed in descriptors, ordered so that the most common ones share their upper
+/// bits and can be grouped three at a time.
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const GENERATOR: [u64; 5] = [0xf5dee51989, 0xa9fdca3312, 0x1bab10e32d, 0x3706b1677a, 0x644d626ffd];
+
+fn polymod(symbols: &[u64]) -> u64 {
+    let mut chk = 1;
+
+    for value in symbols {
+        let top = chk >> 35;
+        chk = (chk & 0x7ffffffff) << 5 ^ value;
+
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if top >> i & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+
+    chk
+}
+
+/// Maps every character to its position in the charset in 5-bit symbols, followed by one
+/// symbol for the upper bits of every group of three characters.
+fn expand(descriptor: &str) -> Result<Vec<u64>, DescriptorError> {
+    let mut symbols = Vec::new();
+    let mut groups = Vec::new();
+
+    for c in descriptor.chars() {
+        let value = INPUT_CHARSET
+            .find(c)
+            .ok_or(DescriptorError::InvalidCharacter(c))? as u64;
+
+        symbols.push(value & 31);
+        groups.push(value >> 5);
+
+        if groups.len() == 3 {
+            symbols.push(groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+
+    match groups.len() {
+        1 => symbols.push(groups[0]),
+        2 => symbols.push(groups[0] * 3 + groups[1]),
+        _ => (),
+    }
+
+    Ok(symbols)
+}
+
+/// Returns the 8 character BIP380 checksum of a descriptor, given without its `#` suffix.
+pub fn descriptor_checksum(descriptor: &str) -> Result<String, DescriptorError> {
+    let mut symbols = expand(descriptor)?;
+    symbols.extend_from_slice(&[0; 8]);
+
+    let checksum = polymod(&symbols) ^ 1;
+
+    Ok((0..8)
+        .map(|i| CHECKSUM_CHARSET[(checksum >> (5 * (7 - i)) & 31) as usize] as char)
+        .collect())
+}
+
+/// Splits a descriptor from its checksum, verifying it if present.
+pub fn verify_checksum(descriptor: &str) -> Result<&str, DescriptorError> {
+    let (body, found) = match descriptor.split_once('#') {
+        Some((body, found)) => (body, Some(found)),
+        None => (descriptor, None),
+    };
+
+    let expected = descriptor_checksum(body)?;
+
+    match found {
+        Some(found) if found != expected => Err(DescriptorError::InvalidChecksum {
+            expected,
+            found: found.to_string(),
+        }),
+        _ => Ok(body),
+    }
+}
+
+#[cfg(test)]
+mod checksum_tests {
+    use super::*;
+
+    const DESCRIPTOR: &str = "pkh([d34db33f/44'/0'/0']xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/1/*)";
+
+    #[test]
+    fn should_return_expected_checksums() {
+        assert_eq!(descriptor_checksum(DESCRIPTOR).unwrap(), "ml40v0wf");
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+    }
+
+    #[test]
+    fn should_verify_checksum() {
+        assert_eq!(verify_checksum("raw(deadbeef)#89f8spxm"), Ok("raw(deadbeef)"));
+        assert_eq!(verify_checksum("raw(deadbeef)"), Ok("raw(deadbeef)"));
+        assert_eq!(
+            verify_checksum("raw(deadbeef)#89f8spxn"),
+            Err(DescriptorError::InvalidChecksum {
+                expected: "89f8spxm".to_string(),
+                found: "89f8spxn".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_character_is_invalid() {
+        assert_eq!(descriptor_checksum("raw(dé)"), Err(DescriptorError::InvalidCharacter('é')));
+    }
+}
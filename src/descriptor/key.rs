@@ -0,0 +1,282 @@
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::XOnlyPublicKey;
+
+use crate::base58decoder::base58decode;
+use crate::descriptor::DescriptorError;
+use crate::key::{
+    DerivationPath, ExtendedKeyError, ExtendedPrivateKey, ExtendedPublicKey, PrivateKey, PublicKey,
+    HARDENED,
+};
+use crate::network::Network;
+
+/// The fingerprint of the master key and the path a key was derived along, e.g.
+/// `[d34db33f/44'/0'/0']`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyOrigin {
+    pub fingerprint: Vec<u8>,
+    pub path: DerivationPath,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeySource {
+    /// A 33-byte compressed or 65-byte uncompressed public key, or a 32-byte x-only one.
+    PublicKey(Vec<u8>),
+    /// A WIF private key, along with the network of its version byte.
+    PrivateKey { key: PrivateKey, compressed: bool, network: Network },
+    ExtendedPublicKey(ExtendedPublicKey),
+    ExtendedPrivateKey(ExtendedPrivateKey),
+}
+
+/// Whether an extended key ends with `/*` or `/*'`, deriving one key per index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wildcard {
+    None,
+    Unhardened,
+    Hardened,
+}
+
+/// A key expression of a descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescriptorKey {
+    pub origin: Option<KeyOrigin>,
+    pub source: KeySource,
+    /// The path derived from an extended key, before the wildcard.
+    pub path: DerivationPath,
+    pub wildcard: Wildcard,
+}
+
+impl DescriptorKey {
+    pub fn is_ranged(&self) -> bool {
+        self.wildcard != Wildcard::None
+    }
+
+    /// Returns whether the key is serialized on 33 bytes or less, as required by segwit.
+    pub fn is_compressed(&self) -> bool {
+        match &self.source {
+            KeySource::PublicKey(key) => key.len() != 65,
+            KeySource::PrivateKey { compressed, .. } => *compressed,
+            _ => true,
+        }
+    }
+
+    /// Returns the size of the serialized public key, without deriving it.
+    pub fn size(&self) -> usize {
+        match &self.source {
+            KeySource::PublicKey(key) => key.len(),
+            _ if self.is_compressed() => 33,
+            _ => 65,
+        }
+    }
+
+    pub fn is_x_only(&self) -> bool {
+        matches!(&self.source, KeySource::PublicKey(key) if key.len() == 32)
+    }
+
+    /// Returns the serialized public key at the given index, which is ignored unless the key
+    /// has a wildcard.
+    pub fn public_key(&self, index: u32) -> Result<Vec<u8>, DescriptorError> {
+        let mut path = self.path.clone();
+
+        match self.wildcard {
+            Wildcard::None => (),
+            Wildcard::Unhardened if index < HARDENED => path = path.child(index),
+            Wildcard::Hardened if index < HARDENED => path = path.child(index + HARDENED),
+            _ => return Err(ExtendedKeyError::InvalidPath(index.to_string()).into()),
+        }
+
+        let key = match &self.source {
+            KeySource::PublicKey(key) => key.clone(),
+            KeySource::PrivateKey { key, compressed, .. } => {
+                let public_key = PublicKey::from_private_key(key.clone());
+
+                match compressed {
+                    true => public_key.compressed,
+                    false => public_key.uncompressed,
+                }
+            }
+            KeySource::ExtendedPublicKey(key) => key.derive_path(&path)?.public_key.compressed,
+            KeySource::ExtendedPrivateKey(key) => key.derive_path(&path)?.get_public_key().compressed,
+        };
+
+        Ok(key)
+    }
+}
+
+impl FromStr for DescriptorKey {
+    type Err = DescriptorError;
+
+    /// Parses a key expression: an optional origin followed by a hex public key, a WIF private
+    /// key, or an extended key with an optional path and wildcard.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DescriptorError::InvalidKey(s.to_string());
+
+        let (origin, key) = match s.strip_prefix('[') {
+            Some(rest) => {
+                let (origin, key) = rest.split_once(']').ok_or_else(invalid)?;
+                let (fingerprint, path) = match origin.split_once('/') {
+                    Some((fingerprint, path)) => (fingerprint, format!("m/{}", path)),
+                    None => (origin, "m".to_string()),
+                };
+                let fingerprint = hex::decode(fingerprint).map_err(|_| invalid())?;
+
+                if fingerprint.len() != 4 {
+                    return Err(invalid());
+                }
+
+                let origin = KeyOrigin { fingerprint, path: DerivationPath::from_str(&path)? };
+
+                (Some(origin), key)
+            }
+            None => (None, s),
+        };
+
+        let mut components = key.split('/');
+        let key = components.next().ok_or_else(invalid)?;
+        let mut components: Vec<&str> = components.collect();
+
+        let wildcard = match components.last() {
+            Some(&"*") => Wildcard::Unhardened,
+            Some(&"*'") | Some(&"*h") | Some(&"*H") => Wildcard::Hardened,
+            _ => Wildcard::None,
+        };
+
+        if wildcard != Wildcard::None {
+            components.pop();
+        }
+
+        components.insert(0, "m");
+
+        let path = DerivationPath::from_str(&components.join("/"))?;
+        let extended = components.len() > 1 || wildcard != Wildcard::None;
+
+        let source = match key.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => {
+                let bytes = hex::decode(key).map_err(|_| invalid())?;
+
+                let valid = match bytes.len() {
+                    32 => XOnlyPublicKey::from_slice(&bytes).is_ok(),
+                    33 | 65 => PublicKey::from_hex(key).is_ok(),
+                    _ => false,
+                };
+
+                if !valid {
+                    return Err(invalid());
+                }
+
+                KeySource::PublicKey(bytes)
+            }
+            false => match key.parse::<ExtendedPrivateKey>() {
+                Ok(key) => KeySource::ExtendedPrivateKey(key),
+                Err(ExtendedKeyError::ExpectedPrivateKey) => {
+                    KeySource::ExtendedPublicKey(key.parse::<ExtendedPublicKey>()?)
+                }
+                Err(_) => {
+                    let (private_key, compressed) = PrivateKey::from_wif(key).map_err(|_| invalid())?;
+                    let version = base58decode(key).map_err(|_| invalid())?.version;
+                    let network = Network::from_wif_version(version).ok_or_else(invalid)?;
+
+                    KeySource::PrivateKey { key: private_key, compressed, network }
+                }
+            },
+        };
+
+        let is_extended_source = matches!(
+            source,
+            KeySource::ExtendedPublicKey(_) | KeySource::ExtendedPrivateKey(_)
+        );
+
+        if extended && !is_extended_source {
+            return Err(invalid());
+        }
+
+        Ok(DescriptorKey { origin, source, path, wildcard })
+    }
+}
+
+impl fmt::Display for DescriptorKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(origin) = &self.origin {
+            let path = origin.path.to_string();
+            write!(f, "[{}{}]", hex::encode(&origin.fingerprint), path.trim_start_matches('m'))?;
+        }
+
+        match &self.source {
+            KeySource::PublicKey(key) => write!(f, "{}", hex::encode(key))?,
+            KeySource::PrivateKey { key, compressed: true, network } => {
+                write!(f, "{}", key.as_wif_compressed(*network))?
+            }
+            KeySource::PrivateKey { key, network, .. } => write!(f, "{}", key.as_wif(*network))?,
+            KeySource::ExtendedPublicKey(key) => write!(f, "{}", key)?,
+            KeySource::ExtendedPrivateKey(key) => write!(f, "{}", key)?,
+        }
+
+        write!(f, "{}", self.path.to_string().trim_start_matches('m'))?;
+
+        match self.wildcard {
+            Wildcard::None => Ok(()),
+            Wildcard::Unhardened => write!(f, "/*"),
+            Wildcard::Hardened => write!(f, "/*'"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod descriptor_key_tests {
+    use super::*;
+    use crate::key::{COMPRESSED_PUBLIC_KEY, COMPRESSED_WIF, UNCOMPRESSED_PUBLIC_KEY};
+
+    const XPUB: &str = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+
+    #[test]
+    fn should_parse_key_with_origin_and_wildcard() {
+        let key = DescriptorKey::from_str(&format!("[d34db33f/44'/0'/0']{}/1/*", XPUB)).unwrap();
+        let origin = key.origin.clone().unwrap();
+
+        assert_eq!(hex::encode(origin.fingerprint), "d34db33f");
+        assert_eq!(origin.path.to_string(), "m/44'/0'/0'");
+        assert_eq!(key.path.to_string(), "m/1");
+        assert_eq!(key.wildcard, Wildcard::Unhardened);
+        assert_eq!(
+            key.public_key(0).unwrap(),
+            ExtendedPublicKey::from_str(XPUB).unwrap().derive_path(&"m/1/0".parse().unwrap()).unwrap().public_key.compressed
+        );
+    }
+
+    #[test]
+    fn should_parse_single_keys() {
+        let key = DescriptorKey::from_str(COMPRESSED_PUBLIC_KEY).unwrap();
+        assert!(key.is_compressed());
+        assert!(!key.is_ranged());
+
+        let key = DescriptorKey::from_str(UNCOMPRESSED_PUBLIC_KEY).unwrap();
+        assert!(!key.is_compressed());
+
+        let key = DescriptorKey::from_str(COMPRESSED_WIF).unwrap();
+        assert_eq!(hex::encode(key.public_key(0).unwrap()), COMPRESSED_PUBLIC_KEY);
+
+        let key = DescriptorKey::from_str(&COMPRESSED_PUBLIC_KEY[2..]).unwrap();
+        assert!(key.is_x_only());
+    }
+
+    #[test]
+    fn should_throw_error_if_key_is_invalid() {
+        for key in [
+            "[d34db33f/44'/0'/0'",
+            "[d34db3/44']03f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a",
+            "03f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a/0",
+            "04f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a",
+            "notakey",
+        ] {
+            assert_eq!(DescriptorKey::from_str(key), Err(DescriptorError::InvalidKey(key.to_string())));
+        }
+
+        let key = DescriptorKey::from_str(&format!("{}/0'", XPUB)).unwrap();
+
+        assert_eq!(
+            key.public_key(0),
+            Err(DescriptorError::InvalidExtendedKey(ExtendedKeyError::HardenedFromPublicKey(HARDENED)))
+        );
+    }
+}
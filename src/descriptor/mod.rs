@@ -0,0 +1,454 @@
+mod checksum;
+mod key;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::address::{Address, AddressError};
+use crate::key::{taproot_tweak_public_key, ExtendedKeyError, Key};
+use crate::network::Network;
+use crate::script::opcodes::{OP_CHECKMULTISIG, OP_CHECKSIG};
+use crate::script::{minimal_push_opcode, push_data, Script, ScriptError};
+
+pub use checksum::{descriptor_checksum, verify_checksum};
+pub use key::{DescriptorKey, KeyOrigin, KeySource, Wildcard};
+
+#[derive(Debug, PartialEq)]
+pub enum DescriptorError {
+    InvalidChecksum { expected: String, found: String },
+    InvalidCharacter(char),
+    InvalidSyntax(String),
+    UnknownFunction(String),
+    /// A function used where it is not allowed, e.g. `wpkh()` within `wsh()`.
+    InvalidContext(String),
+    InvalidKey(String),
+    /// Uncompressed keys are not allowed within segwit and taproot descriptors.
+    UncompressedKey(String),
+    InvalidThreshold { threshold: usize, keys: usize },
+    /// The redeem script of a `sh()` descriptor would be this many bytes, more than can be pushed.
+    ScriptTooLarge(usize),
+    InvalidExtendedKey(ExtendedKeyError),
+    InvalidAddress(AddressError),
+    InvalidScript(ScriptError),
+}
+
+impl From<ExtendedKeyError> for DescriptorError {
+    fn from(err: ExtendedKeyError) -> Self {
+        DescriptorError::InvalidExtendedKey(err)
+    }
+}
+
+impl From<AddressError> for DescriptorError {
+    fn from(err: AddressError) -> Self {
+        DescriptorError::InvalidAddress(err)
+    }
+}
+
+impl From<ScriptError> for DescriptorError {
+    fn from(err: ScriptError) -> Self {
+        DescriptorError::InvalidScript(err)
+    }
+}
+
+/// Where a descriptor is nested, which restricts the functions and keys allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Top,
+    Sh,
+    Wsh,
+}
+
+/// The most keys of a multisig script, as allowed by BIP383.
+const MAX_MULTISIG_KEYS: usize = 20;
+
+/// The most keys of a bare multisig script, as Bitcoin Core relays no larger ones.
+const MAX_BARE_MULTISIG_KEYS: usize = 3;
+
+/// The most bytes a push can hold, which limits the size of P2SH redeem scripts.
+const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
+/// A BIP380 output script descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Descriptor {
+    Pk(DescriptorKey),
+    Pkh(DescriptorKey),
+    Wpkh(DescriptorKey),
+    Sh(Box<Descriptor>),
+    Wsh(Box<Descriptor>),
+    /// `multi()`, or `sortedmulti()` when the keys are sorted before building the script.
+    Multi { threshold: usize, keys: Vec<DescriptorKey>, sorted: bool },
+    /// A taproot output with a key-path spend only.
+    Tr(DescriptorKey),
+    Addr(Address),
+    Raw(Script),
+}
+
+impl Descriptor {
+    /// Returns whether the descriptor has a key with a wildcard, i.e. a script per index.
+    pub fn is_ranged(&self) -> bool {
+        match self {
+            Descriptor::Pk(key) | Descriptor::Pkh(key) | Descriptor::Wpkh(key) | Descriptor::Tr(key) => {
+                key.is_ranged()
+            }
+            Descriptor::Sh(inner) | Descriptor::Wsh(inner) => inner.is_ranged(),
+            Descriptor::Multi { keys, .. } => keys.iter().any(|key| key.is_ranged()),
+            Descriptor::Addr(_) | Descriptor::Raw(_) => false,
+        }
+    }
+
+    /// Returns the script of the descriptor at an index, the scriptPubKey for top level
+    /// descriptors or the redeem or witness script for the ones nested in `sh()` and `wsh()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index the wildcards are replaced with, ignored if not ranged.
+    pub fn script_pubkey(&self, index: u32) -> Result<Script, DescriptorError> {
+        let bytes = match self {
+            Descriptor::Pk(key) => {
+                let mut script = Vec::new();
+                let key = key.public_key(index)?;
+                push_data(&mut script, minimal_push_opcode(key.len()), &key)?;
+                script.push(OP_CHECKSIG);
+                script
+            }
+            Descriptor::Pkh(key) => {
+                Address::p2pkh(key.public_key(index)?.hash160(), Network::Mainnet).script_pubkey()
+            }
+            Descriptor::Wpkh(key) => {
+                Address::segwit(0, key.public_key(index)?.hash160(), Network::Mainnet).script_pubkey()
+            }
+            Descriptor::Sh(inner) => {
+                Address::p2sh(inner.script_pubkey(index)?.bytes.hash160(), Network::Mainnet).script_pubkey()
+            }
+            Descriptor::Wsh(inner) => {
+                Address::segwit(0, inner.script_pubkey(index)?.bytes.sha256(), Network::Mainnet).script_pubkey()
+            }
+            Descriptor::Multi { threshold, keys, sorted } => {
+                let mut keys = keys
+                    .iter()
+                    .map(|key| key.public_key(index))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if *sorted {
+                    keys.sort();
+                }
+
+                let mut script = Vec::new();
+                push_number(&mut script, *threshold);
+
+                for key in keys.iter() {
+                    push_data(&mut script, minimal_push_opcode(key.len()), key)?;
+                }
+
+                push_number(&mut script, keys.len());
+                script.push(OP_CHECKMULTISIG);
+                script
+            }
+            Descriptor::Tr(key) => {
+                let key = key.public_key(index)?;
+                let internal_key = if key.len() == 32 { &key[..] } else { &key[1..] };
+                let (output_key, _) = taproot_tweak_public_key(internal_key, None)
+                    .map_err(|err| DescriptorError::InvalidExtendedKey(err.into()))?;
+
+                Address::segwit(1, output_key, Network::Mainnet).script_pubkey()
+            }
+            Descriptor::Addr(address) => address.script_pubkey(),
+            Descriptor::Raw(script) => script.bytes.clone(),
+        };
+
+        Ok(Script::new(bytes))
+    }
+
+    /// Returns the address of the descriptor at an index, `None` if its script has none such
+    /// as bare multisig. `addr()` descriptors keep the network of their address.
+    pub fn address(&self, index: u32, network: Network) -> Result<Option<Address>, DescriptorError> {
+        match self {
+            Descriptor::Addr(address) => Ok(Some(address.clone())),
+            _ => Ok(Address::from_script_pubkey(&self.script_pubkey(index)?.bytes, network)),
+        }
+    }
+
+    fn parse(s: &str, context: Context) -> Result<Self, DescriptorError> {
+        let (name, args) = split_function(s)?;
+
+        let context_error = || Err(DescriptorError::InvalidContext(name.to_string()));
+
+        let descriptor = match name {
+            "pk" => Descriptor::Pk(parse_key(args, context)?),
+            "pkh" => Descriptor::Pkh(parse_key(args, context)?),
+            "wpkh" if context == Context::Wsh => return context_error(),
+            "wpkh" => Descriptor::Wpkh(parse_key(args, Context::Wsh)?),
+            "sh" if context != Context::Top => return context_error(),
+            "sh" => Descriptor::Sh(Box::new(Descriptor::parse(args, Context::Sh)?)),
+            "wsh" if context == Context::Wsh => return context_error(),
+            "wsh" => Descriptor::Wsh(Box::new(Descriptor::parse(args, Context::Wsh)?)),
+            "multi" | "sortedmulti" => {
+                let mut args = split_arguments(args).into_iter();
+                let threshold = args.next().unwrap_or_default();
+                let keys = args.map(|key| parse_key(key, context)).collect::<Result<Vec<_>, _>>()?;
+
+                let threshold = threshold
+                    .parse::<usize>()
+                    .map_err(|_| DescriptorError::InvalidSyntax(threshold.to_string()))?;
+
+                let max_keys = if context == Context::Top { MAX_BARE_MULTISIG_KEYS } else { MAX_MULTISIG_KEYS };
+
+                if threshold == 0 || threshold > keys.len() || keys.len() > max_keys {
+                    return Err(DescriptorError::InvalidThreshold { threshold, keys: keys.len() });
+                }
+
+                // The keys are pushed with a byte each, along with the threshold, the key count
+                // and OP_CHECKMULTISIG.
+                let script_size = keys.iter().map(|key| key.size() + 1).sum::<usize>() + 3;
+
+                if context == Context::Sh && script_size > MAX_SCRIPT_ELEMENT_SIZE {
+                    return Err(DescriptorError::ScriptTooLarge(script_size));
+                }
+
+                Descriptor::Multi { threshold, keys, sorted: name == "sortedmulti" }
+            }
+            "tr" if context != Context::Top => return context_error(),
+            "tr" => {
+                if split_arguments(args).len() > 1 {
+                    return Err(DescriptorError::InvalidSyntax(args.to_string()));
+                }
+
+                Descriptor::Tr(parse_key(args, Context::Wsh)?)
+            }
+            "addr" if context != Context::Top => return context_error(),
+            "addr" => Descriptor::Addr(Address::from_str(args)?),
+            "raw" if context != Context::Top => return context_error(),
+            "raw" => Descriptor::Raw(Script::from_hex(args)?),
+            _ => return Err(DescriptorError::UnknownFunction(name.to_string())),
+        };
+
+        // x-only keys only make sense as taproot internal keys.
+        let x_only = match &descriptor {
+            Descriptor::Pk(key) | Descriptor::Pkh(key) | Descriptor::Wpkh(key) => key.is_x_only(),
+            Descriptor::Multi { keys, .. } => keys.iter().any(|key| key.is_x_only()),
+            _ => false,
+        };
+
+        if x_only {
+            return Err(DescriptorError::InvalidKey(args.to_string()));
+        }
+
+        Ok(descriptor)
+    }
+}
+
+impl FromStr for Descriptor {
+    type Err = DescriptorError;
+
+    /// Parses a descriptor, verifying its checksum if it has one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Descriptor::parse(verify_checksum(s)?, Context::Top)
+    }
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Descriptor::Pk(key) => write!(f, "pk({})", key),
+            Descriptor::Pkh(key) => write!(f, "pkh({})", key),
+            Descriptor::Wpkh(key) => write!(f, "wpkh({})", key),
+            Descriptor::Sh(inner) => write!(f, "sh({})", inner),
+            Descriptor::Wsh(inner) => write!(f, "wsh({})", inner),
+            Descriptor::Multi { threshold, keys, sorted } => {
+                let name = if *sorted { "sortedmulti" } else { "multi" };
+                let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+
+                write!(f, "{}({},{})", name, threshold, keys.join(","))
+            }
+            Descriptor::Tr(key) => write!(f, "tr({})", key),
+            Descriptor::Addr(address) => write!(f, "addr({})", address),
+            Descriptor::Raw(script) => write!(f, "raw({})", script.as_hex_string()),
+        }
+    }
+}
+
+/// Splits `name(args)` into its name and arguments.
+fn split_function(s: &str) -> Result<(&str, &str), DescriptorError> {
+    let invalid = || DescriptorError::InvalidSyntax(s.to_string());
+
+    let (name, rest) = s.split_once('(').ok_or_else(invalid)?;
+    let args = rest.strip_suffix(')').ok_or_else(invalid)?;
+
+    Ok((name, args))
+}
+
+/// Splits arguments on the commas that are not nested within parentheses or brackets.
+fn split_arguments(args: &str) -> Vec<&str> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    arguments.push(&args[start..]);
+    arguments
+}
+
+/// Parses a key, rejecting uncompressed keys within segwit contexts. `wpkh()` and `tr()` keys
+/// are parsed with the `wsh()` context for that matter.
+fn parse_key(s: &str, context: Context) -> Result<DescriptorKey, DescriptorError> {
+    let key = DescriptorKey::from_str(s)?;
+
+    if context == Context::Wsh && !key.is_compressed() {
+        return Err(DescriptorError::UncompressedKey(s.to_string()));
+    }
+
+    Ok(key)
+}
+
+/// Pushes a multisig threshold or key count, with OP_1 to OP_16 or a one byte data push above 16.
+fn push_number(script: &mut Vec<u8>, n: usize) {
+    match n {
+        1..=16 => script.push(0x50 + n as u8),
+        _ => script.extend_from_slice(&[0x01, n as u8]),
+    }
+}
+
+#[cfg(test)]
+mod descriptor_tests {
+    use super::*;
+    use crate::key::UNCOMPRESSED_PUBLIC_KEY;
+
+    const KEY_A: &str = "022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01";
+    const KEY_B: &str = "03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe";
+
+    fn address(descriptor: &str, index: u32) -> String {
+        Descriptor::from_str(descriptor)
+            .unwrap()
+            .address(index, Network::Mainnet)
+            .unwrap()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn should_derive_ranged_wpkh_addresses() {
+        let descriptor = "wpkh([73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)#wc3n3van";
+
+        assert!(Descriptor::from_str(descriptor).unwrap().is_ranged());
+        assert_eq!(address(descriptor, 0), "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu");
+    }
+
+    #[test]
+    fn should_derive_taproot_address() {
+        let descriptor = "tr(xpub6BgBgsespWvERF3LHQu6CnqdvfEvtMcQjYrcRzx53QJjSxarj2afYWcLteoGVky7D3UKDP9QyrLprQ3VCECoY49yfdDEHGCtMMj92pReUsQ/0/*)";
+
+        assert_eq!(address(descriptor, 0), "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr");
+    }
+
+    #[test]
+    fn should_build_multisig_scripts() {
+        let multi = format!("multi(2,{},{})", KEY_A, KEY_B);
+        let sorted = format!("sortedmulti(2,{},{})", KEY_B, KEY_A);
+
+        assert_eq!(
+            Descriptor::from_str(&multi).unwrap().script_pubkey(0).unwrap(),
+            Descriptor::from_str(&sorted).unwrap().script_pubkey(0).unwrap()
+        );
+        assert_eq!(
+            Descriptor::from_str(&format!("sh({})", sorted)).unwrap().script_pubkey(0).unwrap().as_hex_string(),
+            "a914a6a8b030a38762f4c1f5cbe387b61a3c5da5cd2687"
+        );
+        assert_eq!(address(&format!("sh({})", multi), 0), "3GtEB3yg3r5de2cDJG48SkQwxfxJumKQdN");
+        assert_eq!(
+            Descriptor::from_str(&format!("wsh({})", multi)).unwrap().script_pubkey(0).unwrap().as_hex_string(),
+            "00202a425b3d28474bfb30aa4fba36c70eadaaaed87c3d211ff3985fca26084c2103"
+        );
+        assert_eq!(address(&format!("sh(wsh({}))", multi), 0), "3JBjEwcALoDzVM2NtKerLEfq8FWYSD8qp5");
+        assert_eq!(Descriptor::from_str(&multi).unwrap().address(0, Network::Mainnet), Ok(None));
+    }
+
+    #[test]
+    fn should_build_multisig_scripts_with_more_than_16_keys() {
+        let keys = |n: usize| [KEY_A, KEY_B].repeat(n / 2).join(",");
+        let descriptor = Descriptor::from_str(&format!("wsh(multi(17,{}))", keys(20))).unwrap();
+
+        let Descriptor::Wsh(multi) = descriptor else { panic!("expected a wsh descriptor") };
+        let script = multi.script_pubkey(0).unwrap().bytes;
+
+        assert_eq!(&script[..3], &[0x01, 0x11, 0x21]);
+        assert_eq!(&script[script.len() - 3..], &[0x01, 0x14, OP_CHECKMULTISIG]);
+        assert_eq!(
+            Descriptor::from_str(&format!("wsh(multi(1,{}))", keys(22))),
+            Err(DescriptorError::InvalidThreshold { threshold: 1, keys: 22 })
+        );
+    }
+
+    #[test]
+    fn should_limit_p2sh_multisig_script_size() {
+        let keys = |n: usize| vec![KEY_A; n].join(",");
+        let sh = |n: usize| Descriptor::from_str(&format!("sh(multi(1,{}))", keys(n)));
+
+        assert_eq!(sh(15).unwrap().script_pubkey(0).unwrap().bytes.len(), 23);
+        assert_eq!(sh(16), Err(DescriptorError::ScriptTooLarge(547)));
+        assert_eq!(
+            Descriptor::from_str(&format!("sh(multi(1,{},{}))", keys(7), [UNCOMPRESSED_PUBLIC_KEY; 8].join(","))),
+            Err(DescriptorError::ScriptTooLarge(769))
+        );
+        assert!(Descriptor::from_str(&format!("multi(1,{})", keys(3))).is_ok());
+        assert_eq!(
+            Descriptor::from_str(&format!("multi(1,{})", keys(4))),
+            Err(DescriptorError::InvalidThreshold { threshold: 1, keys: 4 })
+        );
+    }
+
+    #[test]
+    fn should_parse_addr_and_raw() {
+        let raw = Descriptor::from_str("raw(deadbeef)#89f8spxm").unwrap();
+
+        assert_eq!(raw.script_pubkey(0).unwrap().as_hex_string(), "deadbeef");
+        assert_eq!(address("addr(3GtEB3yg3r5de2cDJG48SkQwxfxJumKQdN)", 0), "3GtEB3yg3r5de2cDJG48SkQwxfxJumKQdN");
+    }
+
+    #[test]
+    fn should_display_descriptor() {
+        let descriptor = format!("sh(wsh(sortedmulti(1,[d34db33f/48'/0'/0'/2']{},{})))", KEY_A, KEY_B);
+
+        assert_eq!(Descriptor::from_str(&descriptor).unwrap().to_string(), descriptor);
+    }
+
+    #[test]
+    fn should_throw_error_if_descriptor_is_invalid() {
+        let uncompressed = format!("wpkh({})", UNCOMPRESSED_PUBLIC_KEY);
+
+        assert_eq!(
+            Descriptor::from_str(&uncompressed),
+            Err(DescriptorError::UncompressedKey(UNCOMPRESSED_PUBLIC_KEY.to_string()))
+        );
+        assert_eq!(
+            Descriptor::from_str(&format!("wsh(wpkh({}))", KEY_A)),
+            Err(DescriptorError::InvalidContext("wpkh".to_string()))
+        );
+        assert_eq!(
+            Descriptor::from_str(&format!("sh(tr({}))", KEY_A)),
+            Err(DescriptorError::InvalidContext("tr".to_string()))
+        );
+        assert_eq!(
+            Descriptor::from_str(&format!("multi(3,{},{})", KEY_A, KEY_B)),
+            Err(DescriptorError::InvalidThreshold { threshold: 3, keys: 2 })
+        );
+        assert_eq!(
+            Descriptor::from_str(&format!("foo({})", KEY_A)),
+            Err(DescriptorError::UnknownFunction("foo".to_string()))
+        );
+        assert_eq!(
+            Descriptor::from_str(&format!("pkh({}", KEY_A)),
+            Err(DescriptorError::InvalidSyntax(format!("pkh({}", KEY_A)))
+        );
+    }
+}
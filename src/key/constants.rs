@@ -5,9 +5,23 @@ pub const INVALID_PRIVATE_KEY: &str = "fffffffffffffffffffffffffffffffffffffffff
 pub const COMPRESSED_PRIVATE_KEY: &str = "1e99423a4ed27608a15a2616a2b0e9e52ced330ac530edcc32c8ffc6a526aedd01";
 pub const WIF: &str = "5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jcn";
 pub const COMPRESSED_WIF: &str = "KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtJ";
+pub const TESTNET_WIF: &str = "91pPmKypfMGxN73N3iCjLuwBjgo7F4CpGQtFuE9FziSieVTY4jn";
+pub const TESTNET_COMPRESSED_WIF: &str = "cNcBUemoNGVRN9fRtxrmtteAPQeWZ399d2REmX1TBjvWpRfNMy91";
 
 pub const COMPRESSED_PUBLIC_KEY: &str = "03f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a";
 pub const UNCOMPRESSED_PUBLIC_KEY: &str = "04f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a07cf33da18bd734c600b96a72bbc4749d5141c90ec8ac328ae52ddfe2e505bdb";
 
 pub const ADDRESS_FROM_COMPRESSED: &str = "1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy";
 pub const ADDRESS_FROM_UNCOMPRESSED: &str = "1424C2F4bC9JidNjjTUZCbUxv6Sa1Mt62x";
+pub const TESTNET_ADDRESS_FROM_COMPRESSED: &str = "mxdivjAqQSQj4LrAMX1XLQidyfU3pCWeS7";
+pub const TESTNET_ADDRESS_FROM_UNCOMPRESSED: &str = "miY1V5L3QDaZVjrMT2Sw2WhHn63GzsNFQB";
+
+pub const P2WPKH_ADDRESS: &str = "bc1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmmfxuck";
+pub const TESTNET_P2WPKH_ADDRESS: &str = "tb1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnm30a0r9";
+pub const REGTEST_P2WPKH_ADDRESS: &str = "bcrt1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmnxyz5v";
+
+pub const P2SH_P2WPKH_ADDRESS: &str = "3FyC6EYuxW22uj4CaEGjNCjxeg7gHyFeVv";
+pub const TESTNET_P2SH_P2WPKH_ADDRESS: &str = "2N7XQ9yUwZxXP7WgkFMtbz9jDs2Kr2njYRy";
+
+pub const P2TR_ADDRESS: &str = "bc1psce0qeg5n7fy7lmchmsg9nqelq09yflh2fsj02v2jldeyns6zqkqgsjqpt";
+pub const TESTNET_P2TR_ADDRESS: &str = "tb1psce0qeg5n7fy7lmchmsg9nqelq09yflh2fsj02v2jldeyns6zqkqlcy0my";
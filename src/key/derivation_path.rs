@@ -0,0 +1,106 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::key::ExtendedKeyError;
+
+/// Child numbers from this one onwards are hardened.
+pub const HARDENED: u32 = 0x80000000;
+
+/// A BIP32 derivation path such as `m/84'/0'/0'/0/5`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DerivationPath {
+    /// The child numbers to derive, hardened ones being offset by `HARDENED`.
+    pub children: Vec<u32>,
+}
+
+impl DerivationPath {
+    /// Returns the path followed by the given child number.
+    pub fn child(&self, child_number: u32) -> Self {
+        let mut children = self.children.clone();
+        children.push(child_number);
+
+        DerivationPath { children }
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = ExtendedKeyError;
+
+    /// Parses a path, with or without its leading `m`. Hardened child numbers are marked with
+    /// a trailing `'`, `h` or `H`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.trim().split('/').peekable();
+
+        if components.peek() == Some(&"m") {
+            components.next();
+        }
+
+        let children = components
+            .map(|component| {
+                let (index, hardened) = match component.strip_suffix(['\'', 'h', 'H']) {
+                    Some(index) => (index, true),
+                    None => (component, false),
+                };
+
+                match index.parse::<u32>() {
+                    Ok(number) if number < HARDENED && !index.starts_with('+') => {
+                        Ok(if hardened { number + HARDENED } else { number })
+                    }
+                    _ => Err(ExtendedKeyError::InvalidPath(component.to_string())),
+                }
+            })
+            .collect::<Result<Vec<u32>, ExtendedKeyError>>()?;
+
+        Ok(DerivationPath { children })
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+
+        for child in &self.children {
+            match *child >= HARDENED {
+                true => write!(f, "/{}'", child - HARDENED)?,
+                false => write!(f, "/{}", child)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod derivation_path_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_hardened_and_normal_child_numbers() {
+        let path = DerivationPath::from_str("m/84'/0h/0H/0/5").unwrap();
+
+        assert_eq!(path.children, vec![HARDENED + 84, HARDENED, HARDENED, 0, 5]);
+        assert_eq!(path.to_string(), "m/84'/0'/0'/0/5");
+    }
+
+    #[test]
+    fn should_parse_master_and_relative_paths() {
+        assert_eq!(DerivationPath::from_str("m").unwrap(), DerivationPath::default());
+        assert_eq!(DerivationPath::from_str("0/1").unwrap().children, vec![0, 1]);
+    }
+
+    #[test]
+    fn should_throw_error_if_path_is_invalid() {
+        assert_eq!(
+            DerivationPath::from_str("m/2147483648"),
+            Err(ExtendedKeyError::InvalidPath("2147483648".to_string()))
+        );
+        assert_eq!(
+            DerivationPath::from_str("m/0//1"),
+            Err(ExtendedKeyError::InvalidPath("".to_string()))
+        );
+        assert_eq!(
+            DerivationPath::from_str("m/x'"),
+            Err(ExtendedKeyError::InvalidPath("x'".to_string()))
+        );
+    }
+}
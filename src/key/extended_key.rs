@@ -0,0 +1,525 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crypto::{hmac::Hmac, mac::Mac, sha2::Sha512};
+use secp256k1::{Secp256k1, SecretKey};
+
+use crate::base58decoder::{base58decode, base58encode, Base58DecodeError, Payload, EXTENDED_KEY_VERSIONS};
+use crate::key::{DerivationPath, Key, PrivateKey, PublicKey, PublicKeyError, HARDENED};
+use crate::network::Network;
+
+#[derive(Debug, PartialEq)]
+pub enum ExtendedKeyError {
+    /// BIP32 seeds are between 16 and 64 bytes long.
+    InvalidSeedLength(usize),
+    InvalidPath(String),
+    /// Hardened children can only be derived from a private key.
+    HardenedFromPublicKey(u32),
+    MaxDepthExceeded,
+    /// The derived key is invalid, which happens with a probability lower than 1 in 2^127. The
+    /// next child number should be used instead.
+    InvalidChildKey(u32),
+    InvalidBase58(Base58DecodeError),
+    InvalidLength(usize),
+    UnknownVersion(Vec<u8>),
+    UnknownPrefix(String),
+    ExpectedPrivateKey,
+    ExpectedPublicKey,
+    /// Account keys are hardened children at depth 3, e.g. m/84'/0'/0'.
+    NotAnAccountKey,
+    InvalidKey(secp256k1::Error),
+}
+
+impl From<Base58DecodeError> for ExtendedKeyError {
+    fn from(err: Base58DecodeError) -> Self {
+        ExtendedKeyError::InvalidBase58(err)
+    }
+}
+
+impl From<secp256k1::Error> for ExtendedKeyError {
+    fn from(err: secp256k1::Error) -> Self {
+        ExtendedKeyError::InvalidKey(err)
+    }
+}
+
+impl From<PublicKeyError> for ExtendedKeyError {
+    fn from(err: PublicKeyError) -> Self {
+        match err {
+            PublicKeyError::InvalidKey(err) => ExtendedKeyError::InvalidKey(err),
+            _ => ExtendedKeyError::InvalidKey(secp256k1::Error::InvalidPublicKey),
+        }
+    }
+}
+
+/// A BIP32 extended private key: a private key along with the chain code and position needed to
+/// derive its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPrivateKey {
+    pub network: Network,
+    pub depth: u8,
+    /// The first four bytes of the hash160 of the parent public key, zeros for master keys.
+    pub parent_fingerprint: Vec<u8>,
+    pub child_number: u32,
+    pub chain_code: Vec<u8>,
+    pub private_key: PrivateKey,
+}
+
+/// A BIP32 extended public key, only able to derive non-hardened children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPublicKey {
+    pub network: Network,
+    pub depth: u8,
+    /// The first four bytes of the hash160 of the parent public key, zeros for master keys.
+    pub parent_fingerprint: Vec<u8>,
+    pub child_number: u32,
+    pub chain_code: Vec<u8>,
+    pub public_key: PublicKey,
+}
+
+impl ExtendedPrivateKey {
+    /// Returns the master key of a seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Between 16 and 64 bytes, usually the 64 bytes derived from a BIP39 mnemonic.
+    /// * `network` - The network the key is serialized for, mainnet keys being xprv and the
+    ///   other ones tprv.
+    pub fn from_seed(seed: &[u8], network: Network) -> Result<Self, ExtendedKeyError> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(ExtendedKeyError::InvalidSeedLength(seed.len()));
+        }
+
+        let i = hmac_sha512(b"Bitcoin seed", seed);
+        let key = SecretKey::from_slice(&i[..32])?;
+
+        Ok(ExtendedPrivateKey {
+            network,
+            depth: 0,
+            parent_fingerprint: vec![0x00; 4],
+            child_number: 0,
+            chain_code: i[32..].to_vec(),
+            private_key: PrivateKey { key: key.secret_bytes().to_vec() },
+        })
+    }
+
+    /// Derives the child key of the given child number, hardened if at least `HARDENED`.
+    pub fn derive_child(&self, child_number: u32) -> Result<Self, ExtendedKeyError> {
+        let depth = self.depth.checked_add(1).ok_or(ExtendedKeyError::MaxDepthExceeded)?;
+
+        let mut data = match child_number >= HARDENED {
+            true => [&[0x00], &self.private_key.key[..]].concat(),
+            false => self.get_public_key().compressed,
+        };
+        data.extend_from_slice(&child_number.to_be_bytes());
+
+        let i = hmac_sha512(&self.chain_code, &data);
+        let mut key = SecretKey::from_slice(&self.private_key.key)?;
+
+        key.add_assign(&i[..32])
+            .map_err(|_| ExtendedKeyError::InvalidChildKey(child_number))?;
+
+        Ok(ExtendedPrivateKey {
+            network: self.network,
+            depth,
+            parent_fingerprint: self.fingerprint(),
+            child_number,
+            chain_code: i[32..].to_vec(),
+            private_key: PrivateKey { key: key.secret_bytes().to_vec() },
+        })
+    }
+
+    /// Derives the key at the given path, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, ExtendedKeyError> {
+        path.children
+            .iter()
+            .try_fold(self.clone(), |key, child_number| key.derive_child(*child_number))
+    }
+
+    pub fn get_public_key(&self) -> PublicKey {
+        PublicKey::from_private_key(self.private_key.clone())
+    }
+
+    /// Returns the extended public key with the same chain code and position.
+    pub fn get_extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            network: self.network,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint.clone(),
+            child_number: self.child_number,
+            chain_code: self.chain_code.clone(),
+            public_key: self.get_public_key(),
+        }
+    }
+
+    /// Returns the first four bytes of the hash160 of the public key.
+    pub fn fingerprint(&self) -> Vec<u8> {
+        self.get_public_key().compressed.hash160()[..4].to_vec()
+    }
+}
+
+impl ExtendedPublicKey {
+    /// Derives the non-hardened child key of the given child number.
+    pub fn derive_child(&self, child_number: u32) -> Result<Self, ExtendedKeyError> {
+        if child_number >= HARDENED {
+            return Err(ExtendedKeyError::HardenedFromPublicKey(child_number));
+        }
+
+        let depth = self.depth.checked_add(1).ok_or(ExtendedKeyError::MaxDepthExceeded)?;
+
+        let mut data = self.public_key.compressed.clone();
+        data.extend_from_slice(&child_number.to_be_bytes());
+
+        let i = hmac_sha512(&self.chain_code, &data);
+        let secp = Secp256k1::verification_only();
+        let mut key = secp256k1::PublicKey::from_slice(&self.public_key.compressed)?;
+
+        key.add_exp_assign(&secp, &i[..32])
+            .map_err(|_| ExtendedKeyError::InvalidChildKey(child_number))?;
+
+        Ok(ExtendedPublicKey {
+            network: self.network,
+            depth,
+            parent_fingerprint: self.fingerprint(),
+            child_number,
+            chain_code: i[32..].to_vec(),
+            public_key: PublicKey::from_hex(&hex::encode(key.serialize()))?,
+        })
+    }
+
+    /// Derives the key at the given path, relative to this key. The path cannot be hardened.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, ExtendedKeyError> {
+        path.children
+            .iter()
+            .try_fold(self.clone(), |key, child_number| key.derive_child(*child_number))
+    }
+
+    /// Returns the first four bytes of the hash160 of the public key.
+    pub fn fingerprint(&self) -> Vec<u8> {
+        self.public_key.compressed.clone().hash160()[..4].to_vec()
+    }
+}
+
+/// Returns the HMAC-SHA512 of `data` keyed with `key`.
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut hmac = Hmac::new(Sha512::new(), key);
+    hmac.input(data);
+
+    hmac.result().code().to_vec()
+}
+
+/// Re-encodes an extended key under the version bytes of another SLIP-132 prefix, leaving its
+/// payload untouched.
+///
+/// # Arguments
+///
+/// * `extended_key` - An extended key of any known version, e.g. an xpub.
+/// * `prefix` - The prefix to convert it to, e.g. zpub. Public keys can only be converted to
+///   public prefixes and private keys to private ones.
+pub fn convert_extended_key(extended_key: &str, prefix: &str) -> Result<String, ExtendedKeyError> {
+    let (payload, mut data) = deserialize(extended_key)?;
+
+    let private = match payload {
+        Payload::ExtendedKey { private, .. } => private,
+        _ => return Err(ExtendedKeyError::UnknownVersion(data[..4].to_vec())),
+    };
+
+    let (_, version, _, target_private) = EXTENDED_KEY_VERSIONS
+        .iter()
+        .find(|(name, ..)| *name == prefix)
+        .ok_or_else(|| ExtendedKeyError::UnknownPrefix(prefix.to_string()))?;
+
+    match (private, *target_private) {
+        (false, true) => Err(ExtendedKeyError::ExpectedPrivateKey),
+        (true, false) => Err(ExtendedKeyError::ExpectedPublicKey),
+        _ => {
+            data[..4].copy_from_slice(version);
+
+            Ok(base58encode(&data))
+        }
+    }
+}
+
+/// Returns the version bytes of the xprv/xpub or tprv/tpub serializations.
+fn version_bytes(network: Network, private: bool) -> [u8; 4] {
+    let prefix = match (network, private) {
+        (Network::Mainnet, true) => "xprv",
+        (Network::Mainnet, false) => "xpub",
+        (_, true) => "tprv",
+        (_, false) => "tpub",
+    };
+
+    EXTENDED_KEY_VERSIONS
+        .iter()
+        .find(|(name, ..)| *name == prefix)
+        .map(|(_, bytes, ..)| *bytes)
+        .unwrap()
+}
+
+fn serialize(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: &[u8],
+    child_number: u32,
+    chain_code: &[u8],
+    key: &[u8],
+) -> String {
+    let mut data = version.to_vec();
+
+    data.push(depth);
+    data.extend_from_slice(parent_fingerprint);
+    data.extend_from_slice(&child_number.to_be_bytes());
+    data.extend_from_slice(chain_code);
+    data.extend_from_slice(key);
+
+    base58encode(&data)
+}
+
+/// Decodes an extended key of any known version, SLIP-132 ones included, returning its
+/// payload along with its 78 serialized bytes.
+fn deserialize(extended_key: &str) -> Result<(Payload, Vec<u8>), ExtendedKeyError> {
+    let decoded = base58decode(extended_key)?;
+    let data = decoded.data();
+
+    if data.len() != 78 {
+        return Err(ExtendedKeyError::InvalidLength(data.len()));
+    }
+
+    match decoded.identify() {
+        Payload::Unknown => Err(ExtendedKeyError::UnknownVersion(data[..4].to_vec())),
+        payload => Ok((payload, data)),
+    }
+}
+
+impl fmt::Display for ExtendedPrivateKey {
+    /// Serializes the key as xprv, or tprv outside of mainnet.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = [&[0x00], &self.private_key.key[..]].concat();
+
+        write!(
+            f,
+            "{}",
+            serialize(
+                version_bytes(self.network, true),
+                self.depth,
+                &self.parent_fingerprint,
+                self.child_number,
+                &self.chain_code,
+                &key,
+            )
+        )
+    }
+}
+
+impl fmt::Display for ExtendedPublicKey {
+    /// Serializes the key as xpub, or tpub outside of mainnet.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            serialize(
+                version_bytes(self.network, false),
+                self.depth,
+                &self.parent_fingerprint,
+                self.child_number,
+                &self.chain_code,
+                &self.public_key.compressed,
+            )
+        )
+    }
+}
+
+impl FromStr for ExtendedPrivateKey {
+    type Err = ExtendedKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match deserialize(s)?.0 {
+            Payload::ExtendedKey { network, private: true, depth, fingerprint, child_number, chain_code, key, .. } => {
+                if key[0] != 0x00 {
+                    return Err(ExtendedKeyError::InvalidKey(secp256k1::Error::InvalidSecretKey));
+                }
+
+                let key = SecretKey::from_slice(&key[1..])?;
+
+                Ok(ExtendedPrivateKey {
+                    network,
+                    depth,
+                    parent_fingerprint: fingerprint,
+                    child_number,
+                    chain_code,
+                    private_key: PrivateKey { key: key.secret_bytes().to_vec() },
+                })
+            }
+            _ => Err(ExtendedKeyError::ExpectedPrivateKey),
+        }
+    }
+}
+
+impl FromStr for ExtendedPublicKey {
+    type Err = ExtendedKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match deserialize(s)?.0 {
+            Payload::ExtendedKey { network, private: false, depth, fingerprint, child_number, chain_code, key, .. } => {
+                if key[0] != 0x02 && key[0] != 0x03 {
+                    return Err(ExtendedKeyError::InvalidKey(secp256k1::Error::InvalidPublicKey));
+                }
+
+                Ok(ExtendedPublicKey {
+                    network,
+                    depth,
+                    parent_fingerprint: fingerprint,
+                    child_number,
+                    chain_code,
+                    public_key: PublicKey::from_hex(&hex::encode(key))?,
+                })
+            }
+            _ => Err(ExtendedKeyError::ExpectedPublicKey),
+        }
+    }
+}
+
+#[cfg(test)]
+mod extended_key_tests {
+    use super::*;
+
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+    const MASTER_XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+    const MASTER_XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    fn master() -> ExtendedPrivateKey {
+        ExtendedPrivateKey::from_seed(&hex::decode(SEED).unwrap(), Network::Mainnet).unwrap()
+    }
+
+    fn derive(path: &str) -> ExtendedPrivateKey {
+        master().derive_path(&DerivationPath::from_str(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_return_bip32_master_key() {
+        let master = master();
+
+        assert_eq!(master.to_string(), MASTER_XPRV);
+        assert_eq!(master.get_extended_public_key().to_string(), MASTER_XPUB);
+    }
+
+    #[test]
+    fn should_derive_bip32_test_vector_1() {
+        let key = derive("m/0'/1");
+
+        assert_eq!(key.to_string(), "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs");
+        assert_eq!(key.get_extended_public_key().to_string(), "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ");
+
+        let key = derive("m/0'/1/2'/2/1000000000");
+
+        assert_eq!(key.to_string(), "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76");
+        assert_eq!(key.get_extended_public_key().to_string(), "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy");
+    }
+
+    #[test]
+    fn should_derive_bip32_test_vector_2() {
+        let seed = hex::decode("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542").unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed, Network::Mainnet).unwrap();
+        let path = DerivationPath::from_str("m/0/2147483647'/1/2147483646'/2").unwrap();
+
+        assert_eq!(
+            master.derive_path(&path).unwrap().to_string(),
+            "xprvA2nrNbFZABcdryreWet9Ea4LvTJcGsqrMzxHx98MMrotbir7yrKCEXw7nadnHM8Dq38EGfSh6dqA9QWTyefMLEcBYJUuekgW4BYPJcr9E7j"
+        );
+    }
+
+    #[test]
+    fn should_derive_public_children_from_extended_public_key() {
+        let xpub = ExtendedPublicKey::from_str("xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ").unwrap();
+        let path = DerivationPath::from_str("2/1000000000").unwrap();
+
+        assert_eq!(
+            xpub.derive_path(&path).unwrap(),
+            derive("m/0'/1").derive_path(&path).unwrap().get_extended_public_key()
+        );
+    }
+
+    #[test]
+    fn should_derive_bip84_account_key() {
+        let key = derive("m/84'/0'/0'/0/5");
+
+        assert_eq!(
+            key.private_key.as_hex_string(),
+            "7dd6425b67fedeaece23be1a7e58890cd3b803eebc5f63a38ec86669055140a7"
+        );
+        assert_eq!(
+            hex::encode(key.get_public_key().compressed),
+            "037467517415cd9bebf7417377925c4cac0bddebd69e195a26d6c15728dac4d76c"
+        );
+    }
+
+    #[test]
+    fn should_serialize_testnet_keys_as_tprv() {
+        let seed = hex::decode("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542").unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed, Network::Testnet).unwrap();
+
+        assert_eq!(
+            master.to_string(),
+            "tprv8ZgxMBicQKsPdqC56nGKYsarqYsgrSm33vCswnuMLFCk3gP7DFW5nPFExzSe7FGAzkbAFrxtXoQEe8vaX471tU3dsUUC7PNpYLGuzb2agmj"
+        );
+    }
+
+    #[test]
+    fn should_parse_serialized_keys() {
+        assert_eq!(ExtendedPrivateKey::from_str(MASTER_XPRV).unwrap(), master());
+        assert_eq!(
+            ExtendedPublicKey::from_str(MASTER_XPUB).unwrap(),
+            master().get_extended_public_key()
+        );
+    }
+
+    #[test]
+    fn should_convert_between_slip132_prefixes() {
+        let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+        let xpub = "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V";
+
+        assert_eq!(convert_extended_key(zpub, "xpub").unwrap(), xpub);
+        assert_eq!(convert_extended_key(xpub, "zpub").unwrap(), zpub);
+        assert_eq!(
+            convert_extended_key(xpub, "vpub").unwrap(),
+            "vpub5YvMuJNjRSYon44z9QmCfdf8SqJRVNvz6m55Qy5iVjZQxDfUgtiQjnc7CC1fAbED2tAGCZRERUfvtn2DstZGU6HMns6dXXH2wujSc2wfi2x"
+        );
+        assert_eq!(
+            convert_extended_key(xpub, "Zpub").unwrap(),
+            "Zpub739WFCnqb8H6bozqRWNgL4NwrVvUUDaa5UodTovoPXuLnoVJTvkwKA6b5ioUif4ntuhU53ob9LUdZ66F3uNGoX8S6gzjGa1yvYFtkDRknR2"
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_conversion_is_invalid() {
+        assert_eq!(convert_extended_key(MASTER_XPUB, "zprv"), Err(ExtendedKeyError::ExpectedPrivateKey));
+        assert_eq!(convert_extended_key(MASTER_XPRV, "zpub"), Err(ExtendedKeyError::ExpectedPublicKey));
+        assert_eq!(
+            convert_extended_key(MASTER_XPUB, "wpub"),
+            Err(ExtendedKeyError::UnknownPrefix("wpub".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_deriving_hardened_child_from_public_key() {
+        let xpub = master().get_extended_public_key();
+
+        assert_eq!(
+            xpub.derive_child(HARDENED),
+            Err(ExtendedKeyError::HardenedFromPublicKey(HARDENED))
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_extended_key_is_invalid() {
+        assert_eq!(
+            ExtendedPrivateKey::from_seed(&[0x00; 15], Network::Mainnet),
+            Err(ExtendedKeyError::InvalidSeedLength(15))
+        );
+        assert_eq!(ExtendedPrivateKey::from_str(MASTER_XPUB), Err(ExtendedKeyError::ExpectedPrivateKey));
+        assert_eq!(ExtendedPublicKey::from_str(MASTER_XPRV), Err(ExtendedKeyError::ExpectedPublicKey));
+        assert_eq!(
+            ExtendedPublicKey::from_str("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy"),
+            Err(ExtendedKeyError::InvalidLength(21))
+        );
+    }
+}
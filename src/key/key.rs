@@ -8,12 +8,14 @@ pub trait Key {
     fn as_hex_string(&mut self) -> String;
     fn append_checksum(&mut self) -> ();
     fn hash160(self) -> Vec<u8>;
+    fn sha256(self) -> Vec<u8>;
+    #[allow(clippy::wrong_self_convention)]
     fn as_decimal(self) -> String;
 }
 
 impl Key for Vec<u8> {
     fn from_str(s: &str) -> Result<Vec<u8>, hex::FromHexError> {
-        Ok(s.to_string().to_byte_array()?)
+        s.to_string().to_byte_array()
     }
 
     fn as_hex_string(&mut self) -> String {
@@ -24,7 +26,7 @@ impl Key for Vec<u8> {
         let mut buff = [0x00; 32];
         let mut hasher = Sha256::new();
 
-        hasher.input(&self);
+        hasher.input(self);
         hasher.result(&mut buff);
         hasher.reset();
 
@@ -48,6 +50,16 @@ impl Key for Vec<u8> {
         buff[0..20].to_vec()
     }
 
+    fn sha256(self) -> Vec<u8> {
+        let mut buff = [0x00; 32];
+
+        let mut hasher = Sha256::new();
+        hasher.input(&self);
+        hasher.result(&mut buff);
+
+        buff.to_vec()
+    }
+
     fn as_decimal(self) -> String {
         format!("{}", BigUint::from_bytes_be(&self))
     }
@@ -131,6 +143,14 @@ mod keys_tests {
         )
     }
 
+    #[test]
+    fn sha256_of_empty_input() {
+        assert_eq!(
+            Vec::new().sha256(),
+            hex::decode("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").unwrap(),
+        )
+    }
+
     #[test]
     fn as_decimal() {
         let actual = Vec::from_str("ff").unwrap().as_decimal();
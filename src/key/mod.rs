@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 mod key;
 pub use key::Key;
 
@@ -7,6 +8,19 @@ pub use private_key::PrivateKeyError;
 
 mod public_key;
 pub use public_key::PublicKey;
+pub use public_key::PublicKeyError;
+
+mod taproot;
+pub use taproot::{
+    tagged_hash, tap_branch_hash, tap_leaf_hash, taproot_tweak, taproot_tweak_private_key,
+    taproot_tweak_public_key, TAPROOT_LEAF_TAPSCRIPT,
+};
+
+mod derivation_path;
+pub use derivation_path::{DerivationPath, HARDENED};
+
+mod extended_key;
+pub use extended_key::{convert_extended_key, hmac_sha512, ExtendedKeyError, ExtendedPrivateKey, ExtendedPublicKey};
 
 mod constants;
 pub use constants::*;
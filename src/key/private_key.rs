@@ -1,5 +1,7 @@
+use crate::base58decoder::{base58decode, Base58DecodeError};
 use crate::key::constants::N;
 use crate::key::Key;
+use crate::network::Network;
 use crate::utils::ToByteArray;
 
 #[derive(Debug, PartialEq)]
@@ -7,6 +9,10 @@ pub enum PrivateKeyError {
     GreaterThanCurveOrder,
     InvalidSize,
     InvalidHex(hex::FromHexError),
+    InvalidBase58(bs58::decode::Error),
+    InvalidChecksum,
+    InvalidVersion(u8),
+    InvalidCompressionFlag(u8),
 }
 
 impl From<hex::FromHexError> for PrivateKeyError {
@@ -15,13 +21,23 @@ impl From<hex::FromHexError> for PrivateKeyError {
     }
 }
 
+impl From<Base58DecodeError> for PrivateKeyError {
+    fn from(err: Base58DecodeError) -> Self {
+        match err {
+            Base58DecodeError::InvalidBase58(err) => PrivateKeyError::InvalidBase58(err),
+            Base58DecodeError::TooShort(_) => PrivateKeyError::InvalidSize,
+            Base58DecodeError::InvalidChecksum { .. } => PrivateKeyError::InvalidChecksum,
+        }
+    }
+}
+
 /// A struct representing Secp256k1 private key
 ///
 /// "The private key can be any number between 0 and n - 1, inclusive, where n is a constant
 /// defined as the order of the Secp256k1 elliptic curve."
 ///
 /// n = FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE BAAEDCE6 AF48A03B BFD25E8C D0364141
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PrivateKey {
     pub key: Vec<u8>,
 }
@@ -32,6 +48,7 @@ impl PrivateKey {
     /// # Arguments
     ///
     /// * `privkey` - Private key as a string slice of hexadecimals digits.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(privkey_as_str: &str) -> Result<Self, PrivateKeyError> {
         if privkey_as_str.len() > 64 {
             return Err(PrivateKeyError::InvalidSize);
@@ -45,6 +62,45 @@ impl PrivateKey {
 
         let key = Vec::from_str(&privkey_as_str)?;
 
+        PrivateKey::from_bytes(key)
+    }
+
+    /// Returns a private key struct and whether it should be used compressed, given a WIF string.
+    ///
+    /// # Arguments
+    ///
+    /// * `wif` - Private key encoded in the "Wallet Import Format", compressed or not.
+    pub fn from_wif(wif: &str) -> Result<(Self, bool), PrivateKeyError> {
+        let decoded = base58decode(wif)?;
+
+        let compressed = match decoded.payload.len() {
+            32 => false,
+            33 => true,
+            _ => return Err(PrivateKeyError::InvalidSize),
+        };
+
+        if Network::from_wif_version(decoded.version).is_none() {
+            return Err(PrivateKeyError::InvalidVersion(decoded.version));
+        }
+
+        if compressed && decoded.payload[32] != 0x01 {
+            return Err(PrivateKeyError::InvalidCompressionFlag(decoded.payload[32]));
+        }
+
+        Ok((PrivateKey::from_bytes(decoded.payload[..32].to_vec())?, compressed))
+    }
+
+    /// Returns a private key struct given either a hexadecimal or a WIF string.
+    ///
+    /// Inputs made only of hexadecimal digits are read as hex, anything else as WIF.
+    pub fn from_hex_or_wif(privkey: &str) -> Result<Self, PrivateKeyError> {
+        match privkey.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => PrivateKey::from_str(privkey),
+            false => Ok(PrivateKey::from_wif(privkey)?.0),
+        }
+    }
+
+    fn from_bytes(key: Vec<u8>) -> Result<Self, PrivateKeyError> {
         let less_than_curve_order = key < N.to_string().to_byte_array().unwrap();
 
         match less_than_curve_order {
@@ -54,7 +110,7 @@ impl PrivateKey {
     }
 
     /// Returns a hexadecimal string representing the private key
-    fn as_hex_string(&self) -> String {
+    pub fn as_hex_string(&self) -> String {
         let mut key = self.key.clone();
 
         key.as_hex_string()
@@ -69,20 +125,20 @@ impl PrivateKey {
     }
 
     /// Returns a bs58 encoded string representing the private key in the WIF format.
-    pub fn as_wif(&self) -> String {
+    pub fn as_wif(&self, network: Network) -> String {
         let mut key = self.key.clone();
 
-        key.insert(0, 0x80);
+        key.insert(0, network.wif_version());
         key.append_checksum();
 
         bs58::encode(key).into_string()
     }
 
     /// Returns a bs58 encoded string representing the private key in the WIF-compressed format.
-    pub fn as_wif_compressed(&self) -> String {
+    pub fn as_wif_compressed(&self, network: Network) -> String {
         let mut key = self.key.clone();
 
-        key.insert(0, 0x80);
+        key.insert(0, network.wif_version());
         key.push(0x01);
         key.append_checksum();
 
@@ -98,7 +154,11 @@ impl PrivateKey {
 #[cfg(test)]
 mod private_key_tests {
     use super::{PrivateKey, PrivateKeyError};
-    use crate::key::constants::{COMPRESSED_PRIVATE_KEY, COMPRESSED_WIF, N, PRIVATE_KEY, WIF};
+    use crate::network::Network;
+    use crate::key::constants::{
+        COMPRESSED_PRIVATE_KEY, COMPRESSED_WIF, N, PRIVATE_KEY, TESTNET_COMPRESSED_WIF,
+        TESTNET_WIF, WIF,
+    };
 
     #[test]
     fn constructor_should_return_private_key() {
@@ -162,13 +222,22 @@ mod private_key_tests {
 
     #[test]
     fn should_return_expected_wif_format() {
-        assert_eq!(PrivateKey::from_str(PRIVATE_KEY).unwrap().as_wif(), WIF,)
+        assert_eq!(PrivateKey::from_str(PRIVATE_KEY).unwrap().as_wif(Network::Mainnet), WIF,)
+    }
+
+    #[test]
+    fn should_return_expected_testnet_wif_format() {
+        let pk = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        assert_eq!(pk.as_wif(Network::Testnet), TESTNET_WIF);
+        assert_eq!(pk.as_wif(Network::Regtest), TESTNET_WIF);
+        assert_eq!(pk.as_wif_compressed(Network::Signet), TESTNET_COMPRESSED_WIF);
     }
 
     #[test]
     fn should_return_expected_wif_compressed_format() {
         assert_eq!(
-            PrivateKey::from_str(PRIVATE_KEY).unwrap().as_wif_compressed(),
+            PrivateKey::from_str(PRIVATE_KEY).unwrap().as_wif_compressed(Network::Mainnet),
             COMPRESSED_WIF
         )
     }
@@ -193,4 +262,71 @@ mod private_key_tests {
             expected,
         )
     }
+
+    #[test]
+    fn should_decode_wif() {
+        let (pk, compressed) = PrivateKey::from_wif(WIF).unwrap();
+
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert!(!compressed);
+    }
+
+    #[test]
+    fn should_decode_wif_compressed() {
+        let (pk, compressed) = PrivateKey::from_wif(COMPRESSED_WIF).unwrap();
+
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert!(compressed);
+    }
+
+    #[test]
+    fn should_decode_testnet_wif() {
+        let (pk, compressed) = PrivateKey::from_wif(TESTNET_WIF).unwrap();
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert!(!compressed);
+
+        let (pk, compressed) = PrivateKey::from_wif(TESTNET_COMPRESSED_WIF).unwrap();
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert!(compressed);
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_checksum_is_invalid() {
+        assert_eq!(
+            PrivateKey::from_wif("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jco"),
+            Err(PrivateKeyError::InvalidChecksum),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_version_is_unknown() {
+        assert_eq!(
+            PrivateKey::from_wif("5KzWMpcJvYt3Xs7nKwvpRdz11947jbWVi76R4kUeedi4N5xDszq"),
+            Err(PrivateKeyError::InvalidVersion(0x81)),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_compression_flag_is_invalid() {
+        assert_eq!(
+            PrivateKey::from_wif("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgetQK9K"),
+            Err(PrivateKeyError::InvalidCompressionFlag(0x02)),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_has_invalid_size() {
+        assert_eq!(
+            PrivateKey::from_wif("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy"),
+            Err(PrivateKeyError::InvalidSize),
+        )
+    }
+
+    #[test]
+    fn should_accept_hex_or_wif() {
+        let from_hex = PrivateKey::from_hex_or_wif(PRIVATE_KEY).unwrap();
+        let from_wif = PrivateKey::from_hex_or_wif(COMPRESSED_WIF).unwrap();
+
+        assert_eq!(from_hex, from_wif);
+    }
 }
@@ -1,9 +1,32 @@
-use crate::key::{Key, PrivateKey, PrivateKeyError};
+use crate::bech32;
+use crate::key::{taproot_tweak_public_key, Key, PrivateKey, PrivateKeyError};
+use crate::network::Network;
 use secp256k1::{rand, Secp256k1, SecretKey};
 
 type Coordinates = (String, String);
 
 #[derive(Debug, PartialEq)]
+pub enum PublicKeyError {
+    /// Segwit outputs only commit to 33-byte compressed public keys.
+    UncompressedKey,
+    InvalidKey(secp256k1::Error),
+    InvalidSize,
+    InvalidHex(hex::FromHexError),
+}
+
+impl From<hex::FromHexError> for PublicKeyError {
+    fn from(err: hex::FromHexError) -> Self {
+        PublicKeyError::InvalidHex(err)
+    }
+}
+
+impl From<secp256k1::Error> for PublicKeyError {
+    fn from(err: secp256k1::Error) -> Self {
+        PublicKeyError::InvalidKey(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PublicKey {
     pub compressed: Vec<u8>,
     pub uncompressed: Vec<u8>,
@@ -17,34 +40,116 @@ impl PublicKey {
             &SecretKey::from_slice(&pk.key).unwrap(),
         );
 
+        PublicKey::from_secp256k1(pubkey)
+    }
+
+    /// Returns a public key struct given its SEC encoding as a hex string.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkey` - A 33-byte compressed or 65-byte uncompressed public key, hex encoded. The
+    ///   point must lie on the Secp256k1 curve.
+    pub fn from_hex(pubkey: &str) -> Result<Self, PublicKeyError> {
+        let bytes = hex::decode(pubkey)?;
+
+        if bytes.len() != 33 && bytes.len() != 65 {
+            return Err(PublicKeyError::InvalidSize);
+        }
+
+        Ok(PublicKey::from_secp256k1(secp256k1::PublicKey::from_slice(&bytes)?))
+    }
+
+    pub(crate) fn from_secp256k1(pubkey: secp256k1::PublicKey) -> Self {
         PublicKey {
             compressed: pubkey.serialize().to_vec(),
             uncompressed: pubkey.serialize_uncompressed().to_vec(),
         }
     }
 
+    /// Returns the public key of a private key given as a hexadecimal or WIF string.
     pub fn from_private_key_string(pk: &str) -> Result<Self, PrivateKeyError> {
-        let pk = PrivateKey::from_str(pk)?;
+        let pk = PrivateKey::from_hex_or_wif(pk)?;
 
         Ok(PublicKey::from_private_key(pk))
     }
 
-    pub fn get_address_from_compressed(self) -> String {
+    pub fn get_address_from_compressed(self, network: Network) -> String {
         let mut pkh = self.compressed.hash160();
-        pkh.insert(0, 0x00);
+        pkh.insert(0, network.p2pkh_version());
         pkh.append_checksum();
 
         bs58::encode(&pkh).into_string()
     }
 
-    pub fn get_address_from_uncompressed(self) -> String {
+    pub fn get_address_from_uncompressed(self, network: Network) -> String {
         let mut pkh = self.uncompressed.hash160();
-        pkh.insert(0, 0x00);
+        pkh.insert(0, network.p2pkh_version());
         pkh.append_checksum();
 
         bs58::encode(&pkh).into_string()
     }
 
+    /// Returns the native segwit (P2WPKH) address of the compressed public key.
+    pub fn get_p2wpkh_address(self, network: Network) -> Result<String, PublicKeyError> {
+        let program = self.compressed_hash160()?;
+
+        Ok(bech32::encode_segwit_address(network.hrp(), 0, &program)
+            .expect("20-byte version 0 witness programs are always valid"))
+    }
+
+    /// Returns the P2WPKH witness program wrapped by nested segwit addresses: `0 <hash160>`.
+    pub fn get_p2wpkh_redeem_script(self) -> Result<Vec<u8>, PublicKeyError> {
+        let mut script = vec![0x00, 0x14];
+        script.append(&mut self.compressed_hash160()?);
+
+        Ok(script)
+    }
+
+    /// Returns the nested segwit (P2SH-P2WPKH) address of the compressed public key.
+    pub fn get_p2sh_p2wpkh_address(self, network: Network) -> Result<String, PublicKeyError> {
+        let mut script_hash = self.get_p2wpkh_redeem_script()?.hash160();
+        script_hash.insert(0, network.p2sh_version());
+        script_hash.append_checksum();
+
+        Ok(bs58::encode(&script_hash).into_string())
+    }
+
+    /// Returns the x-only public key used as taproot internal key, i.e. the x coordinate.
+    pub fn get_x_only_public_key(self) -> Result<Vec<u8>, PublicKeyError> {
+        self.check_compressed()?;
+
+        Ok(self.compressed[1..].to_vec())
+    }
+
+    /// Returns the BIP86 taproot output key, the internal key tweaked without a script tree.
+    pub fn get_p2tr_output_key(self) -> Result<Vec<u8>, PublicKeyError> {
+        let internal_key = self.get_x_only_public_key()?;
+        let (output_key, _) = taproot_tweak_public_key(&internal_key, None)?;
+
+        Ok(output_key)
+    }
+
+    /// Returns the BIP86 taproot (P2TR) key-path address of the compressed public key.
+    pub fn get_p2tr_address(self, network: Network) -> Result<String, PublicKeyError> {
+        let output_key = self.get_p2tr_output_key()?;
+
+        Ok(bech32::encode_segwit_address(network.hrp(), 1, &output_key)
+            .expect("32-byte version 1 witness programs are always valid"))
+    }
+
+    fn check_compressed(&self) -> Result<(), PublicKeyError> {
+        match self.compressed.len() == 33 && matches!(self.compressed[0], 0x02 | 0x03) {
+            true => Ok(()),
+            false => Err(PublicKeyError::UncompressedKey),
+        }
+    }
+
+    fn compressed_hash160(self) -> Result<Vec<u8>, PublicKeyError> {
+        self.check_compressed()?;
+
+        Ok(self.compressed.hash160())
+    }
+
     pub fn get_coordinates(self) -> Coordinates {
         (
             hex::encode(&self.uncompressed[1..33]),
@@ -52,7 +157,7 @@ impl PublicKey {
         )
     }
 
-    pub fn vanity_address(vanity: &str) -> String {
+    pub fn vanity_address(vanity: &str, network: Network) -> String {
         loop {
             let secp = Secp256k1::new();
 
@@ -68,7 +173,7 @@ impl PublicKey {
                 uncompressed: pubkey.serialize().to_vec(),
             };
 
-            let compressed_address = &pubkey.get_address_from_compressed();
+            let compressed_address = &pubkey.get_address_from_compressed(network);
             let prefix = &compressed_address.as_str()[1 .. vanity.len() + 1];
 
             if prefix == vanity {
@@ -78,7 +183,7 @@ impl PublicKey {
     }
 
     /// Returns a new address from an compressed public key, derived from a random secret key.
-    pub fn get_new_address() -> String {
+    pub fn get_new_address(network: Network) -> String {
         let secp = Secp256k1::new();
         let secret_key = SecretKey::new(&mut rand::thread_rng());
 
@@ -92,7 +197,7 @@ impl PublicKey {
             uncompressed: pubkey.serialize().to_vec(),
         };
 
-        pubkey.get_address_from_compressed()
+        pubkey.get_address_from_compressed(network)
     }
 }
 
@@ -132,6 +237,42 @@ mod public_key_tests {
         )
     }
 
+    #[test]
+    fn should_parse_compressed_and_uncompressed_public_keys() {
+        let from_compressed = PublicKey::from_hex(constants::COMPRESSED_PUBLIC_KEY).unwrap();
+        let from_uncompressed = PublicKey::from_hex(constants::UNCOMPRESSED_PUBLIC_KEY).unwrap();
+
+        assert_eq!(from_compressed, from_uncompressed);
+        assert_eq!(
+            from_compressed.compressed,
+            hex::decode(constants::COMPRESSED_PUBLIC_KEY).unwrap()
+        );
+        assert_eq!(
+            from_compressed.uncompressed,
+            hex::decode(constants::UNCOMPRESSED_PUBLIC_KEY).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_public_key_is_invalid() {
+        assert_eq!(
+            PublicKey::from_hex("03f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc34"),
+            Err(PublicKeyError::InvalidSize)
+        );
+        assert_eq!(
+            PublicKey::from_hex("05f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a"),
+            Err(PublicKeyError::InvalidKey(secp256k1::Error::InvalidPublicKey))
+        );
+        assert_eq!(
+            PublicKey::from_hex("04f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a07cf33da18bd734c600b96a72bbc4749d5141c90ec8ac328ae52ddfe2e505bdc"),
+            Err(PublicKeyError::InvalidKey(secp256k1::Error::InvalidPublicKey))
+        );
+        assert_eq!(
+            PublicKey::from_hex("zz"),
+            Err(PublicKeyError::InvalidHex(hex::FromHexError::InvalidHexCharacter { c: 'z', index: 0 }))
+        );
+    }
+
     #[test]
     fn testing_for_error() {
         let r = PublicKey::from_private_key_string(
@@ -141,13 +282,23 @@ mod public_key_tests {
         assert_eq!(r, Err(PrivateKeyError::GreaterThanCurveOrder))
     }
 
+    #[test]
+    fn should_return_expected_keys_given_a_wif_string() {
+        let pk = PublicKey::from_private_key_string(constants::COMPRESSED_WIF).unwrap();
+
+        assert_eq!(
+            pk.compressed,
+            hex::decode(constants::COMPRESSED_PUBLIC_KEY).unwrap()
+        );
+    }
+
     #[test]
     fn should_return_expected_address_from_compressed() {
         let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
         let public_key = PublicKey::from_private_key(pk);
 
         assert_eq!(
-            public_key.get_address_from_compressed(),
+            public_key.get_address_from_compressed(Network::Mainnet),
             constants::ADDRESS_FROM_COMPRESSED,
         )
     }
@@ -158,12 +309,136 @@ mod public_key_tests {
         let public_key = PublicKey::from_private_key(pk);
 
         assert_eq!(
-            public_key.get_address_from_uncompressed(),
+            public_key.get_address_from_uncompressed(Network::Mainnet),
             constants::ADDRESS_FROM_UNCOMPRESSED,
         )
 
     }
 
+    #[test]
+    fn should_return_expected_testnet_addresses() {
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_address_from_compressed(Network::Testnet),
+            constants::TESTNET_ADDRESS_FROM_COMPRESSED,
+        );
+
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_address_from_uncompressed(Network::Regtest),
+            constants::TESTNET_ADDRESS_FROM_UNCOMPRESSED,
+        )
+    }
+
+    #[test]
+    fn should_return_expected_p2wpkh_address() {
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2wpkh_address(Network::Mainnet),
+            Ok(constants::P2WPKH_ADDRESS.to_string()),
+        );
+
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2wpkh_address(Network::Signet),
+            Ok(constants::TESTNET_P2WPKH_ADDRESS.to_string()),
+        );
+
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2wpkh_address(Network::Regtest),
+            Ok(constants::REGTEST_P2WPKH_ADDRESS.to_string()),
+        )
+    }
+
+    #[test]
+    fn should_refuse_p2wpkh_address_for_uncompressed_key() {
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey {
+            compressed: public_key.uncompressed.clone(),
+            uncompressed: public_key.uncompressed,
+        };
+
+        assert_eq!(
+            public_key.get_p2wpkh_address(Network::Mainnet),
+            Err(PublicKeyError::UncompressedKey),
+        )
+    }
+
+    #[test]
+    fn should_return_expected_p2sh_p2wpkh_address() {
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2sh_p2wpkh_address(Network::Mainnet),
+            Ok(constants::P2SH_P2WPKH_ADDRESS.to_string()),
+        );
+
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2sh_p2wpkh_address(Network::Testnet),
+            Ok(constants::TESTNET_P2SH_P2WPKH_ADDRESS.to_string()),
+        )
+    }
+
+    #[test]
+    fn should_return_bip49_test_vector_address() {
+        let public_key = PublicKey {
+            compressed: hex::decode("03a1af804ac108a8a51782198c2d034b28bf90c8803f5a53f76276fa69a4eae77f").unwrap(),
+            uncompressed: vec![],
+        };
+
+        assert_eq!(
+            public_key.get_p2sh_p2wpkh_address(Network::Testnet),
+            Ok("2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2".to_string()),
+        )
+    }
+
+    #[test]
+    fn should_return_p2wpkh_redeem_script() {
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+
+        assert_eq!(
+            public_key.get_p2wpkh_redeem_script(),
+            Ok(hex::decode("0014bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap()),
+        )
+    }
+
+    #[test]
+    fn should_return_expected_p2tr_address() {
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2tr_address(Network::Mainnet),
+            Ok(constants::P2TR_ADDRESS.to_string()),
+        );
+
+        let public_key = PublicKey::from_private_key_string(constants::PRIVATE_KEY).unwrap();
+        assert_eq!(
+            public_key.get_p2tr_address(Network::Testnet),
+            Ok(constants::TESTNET_P2TR_ADDRESS.to_string()),
+        )
+    }
+
+    #[test]
+    fn should_return_bip86_test_vector_keys_and_address() {
+        let public_key = || PublicKey {
+            compressed: hex::decode("03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115").unwrap(),
+            uncompressed: vec![],
+        };
+
+        assert_eq!(
+            hex::encode(public_key().get_x_only_public_key().unwrap()),
+            "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+        );
+        assert_eq!(
+            hex::encode(public_key().get_p2tr_output_key().unwrap()),
+            "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+        );
+        assert_eq!(
+            public_key().get_p2tr_address(Network::Mainnet),
+            Ok("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr".to_string()),
+        )
+    }
+
     #[test]
     fn should_return_expected_coordinates_from_public_key() {
         let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
@@ -181,17 +456,24 @@ mod public_key_tests {
     #[test]
     fn should_return_a_vanity_address() {
         let prefix = "Lo";
-        let vanity_address = PublicKey::vanity_address(prefix);
+        let vanity_address = PublicKey::vanity_address(prefix, Network::Mainnet);
 
         assert_eq!(&vanity_address[1..3], "Lo");
     }
 
     #[test]
     fn should_return_an_address() {
-        let address = PublicKey::get_new_address();
+        let address = PublicKey::get_new_address(Network::Mainnet);
 
         assert_eq!(&address[0..1], "1");
         assert!(address.len() >= 26);
         assert!(address.len() <= 36);
     }
+
+    #[test]
+    fn should_return_a_testnet_address() {
+        let address = PublicKey::get_new_address(Network::Signet);
+
+        assert!(address.starts_with('m') || address.starts_with('n'));
+    }
 }
@@ -0,0 +1,198 @@
+use crypto::{digest::Digest, sha2::Sha256};
+use secp256k1::{KeyPair, Secp256k1, SecretKey, XOnlyPublicKey};
+
+use crate::key::PrivateKey;
+use crate::utils::compact_size;
+
+/// The leaf version of BIP342 tapscripts.
+pub const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc0;
+
+/// Returns the BIP340 tagged hash of `data`: SHA256(SHA256(tag) || SHA256(tag) || data).
+pub fn tagged_hash(tag: &str, data: &[u8]) -> Vec<u8> {
+    let mut tag_hash = [0x00; 32];
+    let mut hasher = Sha256::new();
+    hasher.input_str(tag);
+    hasher.result(&mut tag_hash);
+    hasher.reset();
+
+    let mut buff = [0x00; 32];
+    hasher.input(&tag_hash);
+    hasher.input(&tag_hash);
+    hasher.input(data);
+    hasher.result(&mut buff);
+
+    buff.to_vec()
+}
+
+/// Returns the BIP341 TapTweak of an x-only internal key, committing to an optional script tree.
+///
+/// # Arguments
+///
+/// * `internal_key` - The 32-byte x-only internal key.
+/// * `merkle_root` - The 32-byte root of the script tree, `None` for key-path only outputs.
+pub fn taproot_tweak(internal_key: &[u8], merkle_root: Option<&[u8]>) -> Vec<u8> {
+    let mut data = internal_key.to_vec();
+
+    if let Some(merkle_root) = merkle_root {
+        data.extend_from_slice(merkle_root);
+    }
+
+    tagged_hash("TapTweak", &data)
+}
+
+/// Tweaks an x-only internal key into a taproot output key.
+///
+/// # Arguments
+///
+/// * `internal_key` - The 32-byte x-only internal key.
+/// * `merkle_root` - The 32-byte root of the script tree, `None` for key-path only outputs
+///   such as the ones of BIP86.
+///
+/// # Return
+///
+/// * The 32-byte x-only output key, and whether its y coordinate is odd.
+pub fn taproot_tweak_public_key(
+    internal_key: &[u8],
+    merkle_root: Option<&[u8]>,
+) -> Result<(Vec<u8>, bool), secp256k1::Error> {
+    let secp = Secp256k1::verification_only();
+    let mut output_key = XOnlyPublicKey::from_slice(internal_key)?;
+    let tweak = taproot_tweak(internal_key, merkle_root);
+
+    let parity = output_key.tweak_add_assign(&secp, &tweak)?;
+
+    Ok((output_key.serialize().to_vec(), parity.to_u8() == 1))
+}
+
+/// Tweaks a private key into the one of the taproot output key of its x-only public key,
+/// negating it first if its public key has an odd y coordinate as BIP341 requires.
+///
+/// # Arguments
+///
+/// * `private_key` - The key of the internal key.
+/// * `merkle_root` - The 32-byte root of the script tree, `None` for key-path only outputs.
+pub fn taproot_tweak_private_key(
+    private_key: &PrivateKey,
+    merkle_root: Option<&[u8]>,
+) -> Result<PrivateKey, secp256k1::Error> {
+    let secp = Secp256k1::new();
+    let mut key_pair = KeyPair::from_secret_key(&secp, SecretKey::from_slice(&private_key.key)?);
+    let internal_key = key_pair.public_key().serialize();
+
+    key_pair.tweak_add_assign(&secp, &taproot_tweak(&internal_key, merkle_root))?;
+
+    Ok(PrivateKey {
+        key: key_pair.secret_bytes().to_vec(),
+    })
+}
+
+/// Returns the TapLeaf hash of a script, the leaf of script trees and the hash script-path
+/// signatures commit to.
+///
+/// # Arguments
+///
+/// * `leaf_version` - The leaf version, TAPROOT_LEAF_TAPSCRIPT for tapscripts.
+/// * `script` - The script of the leaf.
+pub fn tap_leaf_hash(leaf_version: u8, script: &[u8]) -> Vec<u8> {
+    let mut data = vec![leaf_version];
+    data.extend(compact_size(script.len() as u64));
+    data.extend_from_slice(script);
+
+    tagged_hash("TapLeaf", &data)
+}
+
+/// Returns the TapBranch hash of two nodes of a script tree, which are sorted first.
+pub fn tap_branch_hash(a: &[u8], b: &[u8]) -> Vec<u8> {
+    match a <= b {
+        true => tagged_hash("TapBranch", &[a, b].concat()),
+        false => tagged_hash("TapBranch", &[b, a].concat()),
+    }
+}
+
+#[cfg(test)]
+mod taproot_tests {
+    use super::*;
+
+    #[test]
+    fn should_return_expected_tagged_hash() {
+        assert_eq!(
+            hex::encode(tagged_hash("TapTweak", &[])),
+            "8aa4229474ab0100b2d6f0687f031d1fc9d8eef92a042ad97d279bff456b15e4"
+        )
+    }
+
+    #[test]
+    fn should_tweak_bip86_internal_key() {
+        let internal_key =
+            hex::decode("cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115").unwrap();
+
+        let (output_key, odd) = taproot_tweak_public_key(&internal_key, None).unwrap();
+
+        assert_eq!(
+            hex::encode(output_key),
+            "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+        assert!(odd);
+    }
+
+    /// The second scriptPubKey vector of BIP341, a tree with a single tapscript leaf.
+    #[test]
+    fn should_hash_script_tree_of_single_leaf() {
+        let internal_key =
+            hex::decode("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27")
+                .unwrap();
+        let script =
+            hex::decode("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac")
+                .unwrap();
+
+        let merkle_root = tap_leaf_hash(TAPROOT_LEAF_TAPSCRIPT, &script);
+        let (output_key, _) = taproot_tweak_public_key(&internal_key, Some(&merkle_root)).unwrap();
+
+        assert_eq!(
+            hex::encode(&merkle_root),
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+        );
+        assert_eq!(
+            hex::encode(output_key),
+            "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3"
+        );
+    }
+
+    #[test]
+    fn should_sort_branches() {
+        let (a, b) = ([0x01; 32], [0x02; 32]);
+
+        assert_eq!(tap_branch_hash(&a, &b), tap_branch_hash(&b, &a));
+        assert_eq!(
+            tap_branch_hash(&a, &b),
+            tagged_hash("TapBranch", &[a, b].concat())
+        );
+    }
+
+    /// The second keyPathSpending input of BIP341.
+    #[test]
+    fn should_tweak_private_key() {
+        let private_key = PrivateKey::from_str(
+            "1e4da49f6aaf4e5cd175fe08a32bb5cb4863d963921255f33d3bc31e1343907f",
+        )
+        .unwrap();
+        let merkle_root =
+            hex::decode("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21")
+                .unwrap();
+
+        let tweaked = taproot_tweak_private_key(&private_key, Some(&merkle_root)).unwrap();
+
+        assert_eq!(
+            hex::encode(tweaked.key),
+            "ea260c3b10e60f6de018455cd0278f2f5b7e454be1999572789e6a9565d26080"
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_internal_key_is_invalid() {
+        assert_eq!(
+            taproot_tweak_public_key(&[0x00; 31], None),
+            Err(secp256k1::Error::InvalidPublicKey)
+        )
+    }
+}
@@ -1,3 +1,13 @@
 pub mod utils;
 pub mod key;
 pub mod base58decoder;
+pub mod network;
+pub mod bech32;
+pub mod address;
+pub mod script;
+pub mod mnemonic;
+pub mod account;
+pub mod descriptor;
+pub mod message;
+pub mod signature;
+pub mod transaction;
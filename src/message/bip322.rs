@@ -0,0 +1,526 @@
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::{ecdsa, schnorr, KeyPair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
+
+use crate::address::{Address, AddressError, AddressType};
+use crate::key::{tagged_hash, taproot_tweak, Key, PrivateKey, PublicKey};
+use crate::script::opcodes::OP_RETURN;
+use crate::script::{minimal_push_opcode, push_data, Instruction, Script, ScriptError};
+use crate::transaction::sighash::{legacy_sighash, segwit_v0_sighash, taproot_sighash, SIGHASH_ALL, SIGHASH_DEFAULT};
+use crate::transaction::{deserialize_witness, serialize_witness, Transaction, TxIn, TxOut};
+
+#[derive(Debug, PartialEq)]
+pub enum Bip322Error {
+    InvalidBase64(base64::DecodeError),
+    InvalidAddress(AddressError),
+    /// Only single key P2PKH, P2SH-P2WPKH, P2WPKH and P2TR key-path addresses are supported.
+    UnsupportedAddress(AddressType),
+    /// The simple format only holds a witness, which P2PKH addresses do not use.
+    SimpleFormatUnavailable,
+    /// The signature is neither a witness stack nor a transaction.
+    InvalidEncoding,
+    /// The to_sign transaction does not spend to_spend into a single OP_RETURN output.
+    InvalidTransaction(String),
+    InvalidScript(ScriptError),
+    /// The scriptSig or witness does not hold the items expected for the address type.
+    InvalidWitness,
+    /// The virtual transactions are only signed with SIGHASH_ALL, or SIGHASH_DEFAULT for taproot.
+    UnsupportedSighashType(u8),
+    /// The key of the signature, or of the signer, is not the one of the address.
+    PublicKeyMismatch,
+    InvalidSignature(secp256k1::Error),
+}
+
+impl From<base64::DecodeError> for Bip322Error {
+    fn from(err: base64::DecodeError) -> Self {
+        Bip322Error::InvalidBase64(err)
+    }
+}
+
+impl From<AddressError> for Bip322Error {
+    fn from(err: AddressError) -> Self {
+        Bip322Error::InvalidAddress(err)
+    }
+}
+
+impl From<ScriptError> for Bip322Error {
+    fn from(err: ScriptError) -> Self {
+        Bip322Error::InvalidScript(err)
+    }
+}
+
+impl From<secp256k1::Error> for Bip322Error {
+    fn from(err: secp256k1::Error) -> Self {
+        Bip322Error::InvalidSignature(err)
+    }
+}
+
+/// How a BIP322 signature is encoded: the witness of to_sign only, or the whole transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bip322Format {
+    #[default]
+    Simple,
+    Full,
+}
+
+impl FromStr for Bip322Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "simple" => Ok(Bip322Format::Simple),
+            "full" => Ok(Bip322Format::Full),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Bip322Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bip322Format::Simple => write!(f, "simple"),
+            Bip322Format::Full => write!(f, "full"),
+        }
+    }
+}
+
+/// Returns the BIP322 tagged hash of a message, committed to by the to_spend transaction.
+pub fn message_hash(message: &str) -> Vec<u8> {
+    tagged_hash("BIP0322-signed-message", message.as_bytes())
+}
+
+/// Returns the to_spend transaction, whose only output pays to the address being proven.
+fn to_spend(script_pubkey: &[u8], message: &str) -> Transaction {
+    let mut script_sig = vec![0x00];
+    push_data(&mut script_sig, minimal_push_opcode(32), &message_hash(message))
+        .expect("message hashes are 32 bytes");
+
+    Transaction {
+        version: 0,
+        inputs: vec![TxIn {
+            txid: vec![0x00; 32],
+            vout: 0xffffffff,
+            script_sig,
+            sequence: 0,
+            witness: Vec::new(),
+        }],
+        outputs: vec![TxOut { value: 0, script_pubkey: script_pubkey.to_vec() }],
+        lock_time: 0,
+    }
+}
+
+/// Returns the unsigned to_sign transaction, spending to_spend into an OP_RETURN output.
+fn to_sign(to_spend: &Transaction) -> Transaction {
+    Transaction {
+        version: 0,
+        inputs: vec![TxIn {
+            txid: to_spend.txid(),
+            vout: 0,
+            script_sig: Vec::new(),
+            sequence: 0,
+            witness: Vec::new(),
+        }],
+        outputs: vec![TxOut { value: 0, script_pubkey: vec![OP_RETURN] }],
+        lock_time: 0,
+    }
+}
+
+/// Returns the key-path hash of the input of to_sign, which spends an output worth 0.
+fn key_path_sighash(tx: &Transaction, script_pubkey: &[u8], hash_type: u8) -> Vec<u8> {
+    let prevout = TxOut { value: 0, script_pubkey: script_pubkey.to_vec() };
+
+    taproot_sighash(tx, 0, &[prevout], hash_type, None, None).expect("to_sign has a single input and output")
+}
+
+fn p2pkh_script(hash: &[u8]) -> Vec<u8> {
+    Address::p2pkh(hash.to_vec(), Default::default()).script_pubkey()
+}
+
+fn sign_ecdsa(secret_key: &SecretKey, sighash: &[u8]) -> Vec<u8> {
+    let message = Message::from_slice(sighash).expect("sighashes are 32 bytes");
+    let signature = Secp256k1::signing_only().sign_ecdsa(&message, secret_key);
+
+    let mut bytes = signature.serialize_der().to_vec();
+    bytes.push(SIGHASH_ALL);
+    bytes
+}
+
+/// Signs a message for an address with BIP322.
+///
+/// # Arguments
+///
+/// * `private_key` - The key of the address: its public key for P2PKH, P2SH-P2WPKH and
+///   P2WPKH addresses, or its BIP86 internal key for P2TR addresses.
+/// * `address` - The address the message is signed for.
+/// * `message` - The message, signed as is.
+/// * `format` - Whether to encode the witness only, or the whole to_sign transaction.
+///
+/// # Return
+///
+/// * The base64 encoded signature.
+pub fn sign_message(
+    private_key: &PrivateKey,
+    address: &str,
+    message: &str,
+    format: Bip322Format,
+) -> Result<String, Bip322Error> {
+    let address = Address::from_str(address)?;
+    let script_pubkey = address.script_pubkey();
+    let mut to_sign = to_sign(&to_spend(&script_pubkey, message));
+
+    let secret_key = SecretKey::from_slice(&private_key.key)?;
+    let public_key = PublicKey::from_private_key(private_key.clone());
+    let hash = public_key.compressed.clone().hash160();
+
+    match address.address_type {
+        AddressType::P2pkh => {
+            let key = if address.payload == hash {
+                public_key.compressed
+            } else if address.payload == public_key.uncompressed.clone().hash160() {
+                public_key.uncompressed
+            } else {
+                return Err(Bip322Error::PublicKeyMismatch);
+            };
+
+            let sighash = legacy_sighash(&to_sign, 0, &script_pubkey, SIGHASH_ALL);
+            let signature = sign_ecdsa(&secret_key, &sighash);
+            let mut script_sig = Vec::new();
+            push_data(&mut script_sig, minimal_push_opcode(signature.len()), &signature)?;
+            push_data(&mut script_sig, minimal_push_opcode(key.len()), &key)?;
+
+            to_sign.inputs[0].script_sig = script_sig;
+        }
+        AddressType::P2wpkh | AddressType::P2sh => {
+            let redeem_script = [&[0x00, 0x14], &hash[..]].concat();
+
+            let expected = match address.address_type {
+                AddressType::P2sh => redeem_script.clone().hash160(),
+                _ => hash.clone(),
+            };
+
+            if address.payload != expected {
+                return Err(Bip322Error::PublicKeyMismatch);
+            }
+
+            if address.address_type == AddressType::P2sh {
+                push_data(&mut to_sign.inputs[0].script_sig, minimal_push_opcode(22), &redeem_script)?;
+            }
+
+            // The output spent by to_sign is always worth 0.
+            let sighash = segwit_v0_sighash(&to_sign, 0, &p2pkh_script(&hash), 0, SIGHASH_ALL);
+            let signature = sign_ecdsa(&secret_key, &sighash);
+            to_sign.inputs[0].witness = vec![signature, public_key.compressed];
+        }
+        AddressType::P2tr => {
+            let secp = Secp256k1::new();
+            let internal_key = public_key.compressed[1..].to_vec();
+            let mut key_pair = KeyPair::from_secret_key(&secp, secret_key);
+            key_pair.tweak_add_assign(&secp, &taproot_tweak(&internal_key, None))?;
+
+            if key_pair.public_key().serialize().to_vec() != address.payload {
+                return Err(Bip322Error::PublicKeyMismatch);
+            }
+
+            let sighash = key_path_sighash(&to_sign, &script_pubkey, SIGHASH_DEFAULT);
+            let signature = secp.sign_schnorr(&Message::from_slice(&sighash)?, &key_pair);
+
+            to_sign.inputs[0].witness = vec![signature.as_ref().to_vec()];
+        }
+        address_type => return Err(Bip322Error::UnsupportedAddress(address_type)),
+    }
+
+    match format {
+        Bip322Format::Simple if address.address_type == AddressType::P2pkh => {
+            Err(Bip322Error::SimpleFormatUnavailable)
+        }
+        Bip322Format::Simple => Ok(base64::encode(serialize_witness(&to_sign.inputs[0].witness))),
+        Bip322Format::Full => Ok(base64::encode(to_sign.serialize())),
+    }
+}
+
+/// Verifies a BIP322 signature, simple or full, of a message against an address.
+///
+/// # Return
+///
+/// * `Ok(())` if the signature is valid, or the reason it is not.
+pub fn verify_message(address: &str, signature: &str, message: &str) -> Result<(), Bip322Error> {
+    let address = Address::from_str(address)?;
+    let script_pubkey = address.script_pubkey();
+    let to_spend = to_spend(&script_pubkey, message);
+    let bytes = base64::decode(signature)?;
+
+    let to_sign = match deserialize_witness(&bytes).ok() {
+        Some(witness) => {
+            let mut to_sign = to_sign(&to_spend);
+
+            // The simple format has no scriptSig, the P2SH-P2WPKH one is implied by the key.
+            if let (AddressType::P2sh, Some(public_key)) = (address.address_type, witness.last()) {
+                let redeem_script = [&[0x00, 0x14], &public_key.clone().hash160()[..]].concat();
+                push_data(&mut to_sign.inputs[0].script_sig, minimal_push_opcode(22), &redeem_script)?;
+            }
+
+            to_sign.inputs[0].witness = witness;
+            to_sign
+        }
+        None => {
+            let to_sign = Transaction::deserialize(&bytes).map_err(|_| Bip322Error::InvalidEncoding)?;
+            check_to_sign(&to_sign, &to_spend)?;
+            to_sign
+        }
+    };
+
+    let input = &to_sign.inputs[0];
+    let secp = Secp256k1::verification_only();
+
+    match address.address_type {
+        AddressType::P2pkh => {
+            let instructions = Script::new(input.script_sig.clone()).instructions()?;
+
+            let (signature, public_key) = match (input.witness.is_empty(), &instructions[..]) {
+                (true, [Instruction::Push { data: signature, .. }, Instruction::Push { data: key, .. }]) => {
+                    (signature, key)
+                }
+                _ => return Err(Bip322Error::InvalidWitness),
+            };
+
+            if public_key.clone().hash160() != address.payload {
+                return Err(Bip322Error::PublicKeyMismatch);
+            }
+
+            verify_ecdsa(signature, public_key, &legacy_sighash(&to_sign, 0, &script_pubkey, SIGHASH_ALL))
+        }
+        AddressType::P2wpkh | AddressType::P2sh => {
+            let (signature, public_key) = match &input.witness[..] {
+                [signature, public_key] if public_key.len() == 33 => (signature, public_key),
+                _ => return Err(Bip322Error::InvalidWitness),
+            };
+
+            let hash = public_key.clone().hash160();
+            let redeem_script = [&[0x00, 0x14], &hash[..]].concat();
+
+            let (expected_script_sig, expected_payload) = match address.address_type {
+                AddressType::P2sh => {
+                    let mut script_sig = Vec::new();
+                    push_data(&mut script_sig, minimal_push_opcode(22), &redeem_script)?;
+                    (script_sig, redeem_script.hash160())
+                }
+                _ => (Vec::new(), hash.clone()),
+            };
+
+            if input.script_sig != expected_script_sig {
+                return Err(Bip322Error::InvalidWitness);
+            }
+
+            if expected_payload != address.payload {
+                return Err(Bip322Error::PublicKeyMismatch);
+            }
+
+            let sighash = segwit_v0_sighash(&to_sign, 0, &p2pkh_script(&hash), 0, SIGHASH_ALL);
+            verify_ecdsa(signature, public_key, &sighash)
+        }
+        AddressType::P2tr => {
+            let (signature, hash_type) = match &input.witness[..] {
+                [signature] if input.script_sig.is_empty() && signature.len() == 64 => {
+                    (&signature[..], SIGHASH_DEFAULT)
+                }
+                [signature] if input.script_sig.is_empty() && signature.len() == 65 => {
+                    (&signature[..64], signature[64])
+                }
+                _ => return Err(Bip322Error::InvalidWitness),
+            };
+
+            if hash_type != SIGHASH_ALL && (hash_type != SIGHASH_DEFAULT || signature.len() != 64) {
+                return Err(Bip322Error::UnsupportedSighashType(hash_type));
+            }
+
+            let sighash = key_path_sighash(&to_sign, &script_pubkey, hash_type);
+            let output_key = XOnlyPublicKey::from_slice(&address.payload)?;
+
+            secp.verify_schnorr(
+                &schnorr::Signature::from_slice(signature)?,
+                &Message::from_slice(&sighash)?,
+                &output_key,
+            )?;
+
+            Ok(())
+        }
+        address_type => Err(Bip322Error::UnsupportedAddress(address_type)),
+    }
+}
+
+/// Checks that a to_sign transaction of the full format spends to_spend, and only it, into a
+/// single OP_RETURN output.
+fn check_to_sign(to_sign: &Transaction, to_spend: &Transaction) -> Result<(), Bip322Error> {
+    let expected = self::to_sign(to_spend);
+    let invalid = |reason: &str| Err(Bip322Error::InvalidTransaction(reason.to_string()));
+
+    match to_sign.inputs.as_slice() {
+        [input] if input.txid == expected.inputs[0].txid && input.vout == 0 => (),
+        [_] => return invalid("the input does not spend to_spend"),
+        _ => return invalid("expected a single input"),
+    }
+
+    if to_sign.outputs != expected.outputs {
+        return invalid("expected a single empty OP_RETURN output of 0 satoshis");
+    }
+
+    Ok(())
+}
+
+fn verify_ecdsa(signature: &[u8], public_key: &[u8], sighash: &[u8]) -> Result<(), Bip322Error> {
+    let (hash_type, signature) = signature.split_last().ok_or(Bip322Error::InvalidWitness)?;
+
+    if *hash_type != SIGHASH_ALL {
+        return Err(Bip322Error::UnsupportedSighashType(*hash_type));
+    }
+
+    let signature = ecdsa::Signature::from_der(signature)?;
+    let public_key = secp256k1::PublicKey::from_slice(public_key)?;
+
+    Secp256k1::verification_only().verify_ecdsa(&Message::from_slice(sighash)?, &signature, &public_key)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod bip322_tests {
+    use super::*;
+    use crate::key::{ADDRESS_FROM_UNCOMPRESSED, COMPRESSED_WIF, P2TR_ADDRESS};
+    use crate::transaction::display_txid;
+
+    const WIF: &str = "L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k";
+    const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const P2TR_ADDRESS_OF_WIF: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+
+    fn private_key(wif: &str) -> PrivateKey {
+        PrivateKey::from_wif(wif).unwrap().0
+    }
+
+    #[test]
+    fn should_hash_messages() {
+        assert_eq!(
+            hex::encode(message_hash("")),
+            "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+        );
+        assert_eq!(
+            hex::encode(message_hash("Hello World")),
+            "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+        );
+    }
+
+    #[test]
+    fn should_build_virtual_transactions() {
+        let script_pubkey = Address::from_str(P2WPKH_ADDRESS).unwrap().script_pubkey();
+
+        let vectors = [
+            (
+                "",
+                "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7",
+                "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6",
+            ),
+            (
+                "Hello World",
+                "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b",
+                "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf",
+            ),
+        ];
+
+        for (message, to_spend_txid, to_sign_txid) in vectors {
+            let to_spend = to_spend(&script_pubkey, message);
+
+            assert_eq!(display_txid(&to_spend.txid()), to_spend_txid);
+            assert_eq!(display_txid(&to_sign(&to_spend).txid()), to_sign_txid);
+        }
+    }
+
+    #[test]
+    fn should_sign_p2wpkh_test_vectors() {
+        let vectors = [
+            ("", "AkgwRQIhAPkJ1Q4oYS0htvyuSFHLxRQpFAY56b70UvE7Dxazen0ZAiAtZfFz1S6T6I23MWI2lK/pcNTWncuyL8UL+oMdydVgzAEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"),
+            ("Hello World", "AkgwRQIhAOzyynlqt93lOKJr+wmmxIens//zPzl9tqIOua93wO6MAiBi5n5EyAcPScOjf1lAqIUIQtr3zKNeavYabHyR8eGhowEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"),
+        ];
+
+        for (message, signature) in vectors {
+            assert_eq!(
+                sign_message(&private_key(WIF), P2WPKH_ADDRESS, message, Bip322Format::Simple).unwrap(),
+                signature
+            );
+            assert_eq!(verify_message(P2WPKH_ADDRESS, signature, message), Ok(()));
+        }
+    }
+
+    #[test]
+    fn should_verify_test_vectors() {
+        let vectors = [
+            (P2WPKH_ADDRESS, "", "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="),
+            (P2WPKH_ADDRESS, "Hello World", "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="),
+            (P2TR_ADDRESS_OF_WIF, "Hello World", "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ=="),
+        ];
+
+        for (address, message, signature) in vectors {
+            assert_eq!(verify_message(address, signature, message), Ok(()));
+        }
+    }
+
+    #[test]
+    fn should_sign_and_verify_every_address_type() {
+        let key = private_key(WIF);
+        let public_key = PublicKey::from_private_key(key.clone());
+        let addresses = [
+            public_key.clone().get_address_from_compressed(Default::default()),
+            public_key.clone().get_p2sh_p2wpkh_address(Default::default()).unwrap(),
+            P2WPKH_ADDRESS.to_string(),
+            P2TR_ADDRESS_OF_WIF.to_string(),
+        ];
+
+        for address in addresses.iter() {
+            let full = sign_message(&key, address, "Hello World", Bip322Format::Full).unwrap();
+
+            assert_eq!(verify_message(address, &full, "Hello World"), Ok(()));
+            assert!(verify_message(address, &full, "Hello World!").is_err());
+        }
+
+        for address in addresses[1..].iter() {
+            let simple = sign_message(&key, address, "Hello World", Bip322Format::Simple).unwrap();
+
+            assert_eq!(verify_message(address, &simple, "Hello World"), Ok(()));
+        }
+
+        let uncompressed = PrivateKey::from_wif("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jcn").unwrap().0;
+        let full = sign_message(&uncompressed, ADDRESS_FROM_UNCOMPRESSED, "", Bip322Format::Full).unwrap();
+
+        assert_eq!(verify_message(ADDRESS_FROM_UNCOMPRESSED, &full, ""), Ok(()));
+    }
+
+    #[test]
+    fn should_report_why_verification_failed() {
+        let signature = "AkgwRQIhAOzyynlqt93lOKJr+wmmxIens//zPzl9tqIOua93wO6MAiBi5n5EyAcPScOjf1lAqIUIQtr3zKNeavYabHyR8eGhowEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy";
+
+        assert_eq!(
+            verify_message(P2WPKH_ADDRESS, signature, "Hello World!"),
+            Err(Bip322Error::InvalidSignature(secp256k1::Error::IncorrectSignature))
+        );
+        assert_eq!(
+            verify_message("bc1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmmfxuck", signature, "Hello World"),
+            Err(Bip322Error::PublicKeyMismatch)
+        );
+        assert_eq!(verify_message(P2TR_ADDRESS, signature, "Hello World"), Err(Bip322Error::InvalidWitness));
+        assert_eq!(verify_message(P2WPKH_ADDRESS, "AAAA", "Hello World"), Err(Bip322Error::InvalidEncoding));
+        assert_eq!(
+            sign_message(&private_key(COMPRESSED_WIF), P2WPKH_ADDRESS, "", Bip322Format::Simple),
+            Err(Bip322Error::PublicKeyMismatch)
+        );
+        assert_eq!(
+            sign_message(&private_key(WIF), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", "", Bip322Format::Full),
+            Err(Bip322Error::PublicKeyMismatch)
+        );
+
+        let p2pkh = PublicKey::from_private_key(private_key(WIF)).get_address_from_compressed(Default::default());
+
+        assert_eq!(
+            sign_message(&private_key(WIF), &p2pkh, "", Bip322Format::Simple),
+            Err(Bip322Error::SimpleFormatUnavailable)
+        );
+    }
+}
@@ -0,0 +1,312 @@
+pub mod bip322;
+
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, Secp256k1, SecretKey};
+
+use crate::address::{Address, AddressError, AddressType};
+use crate::key::{Key, PrivateKey, PublicKey};
+use crate::network::Network;
+use crate::utils::compact_size;
+
+const MESSAGE_PREFIX: &str = "Bitcoin Signed Message:\n";
+
+#[derive(Debug, PartialEq)]
+pub enum MessageError {
+    /// The private key is zero or not below the curve order.
+    InvalidPrivateKey(secp256k1::Error),
+    InvalidBase64(base64::DecodeError),
+    InvalidSignatureLength(usize),
+    InvalidHeader(u8),
+    InvalidSignature(secp256k1::Error),
+    InvalidAddress(AddressError),
+    /// Legacy signed messages only commit to keys hashed into P2PKH, P2SH-P2WPKH or P2WPKH
+    /// addresses.
+    UnsupportedAddress(AddressType),
+}
+
+impl From<base64::DecodeError> for MessageError {
+    fn from(err: base64::DecodeError) -> Self {
+        MessageError::InvalidBase64(err)
+    }
+}
+
+impl From<secp256k1::Error> for MessageError {
+    fn from(err: secp256k1::Error) -> Self {
+        MessageError::InvalidSignature(err)
+    }
+}
+
+impl From<AddressError> for MessageError {
+    fn from(err: AddressError) -> Self {
+        MessageError::InvalidAddress(err)
+    }
+}
+
+/// The address type a signature commits to through its header byte, i.e. the first byte of
+/// the signature: 27 to 30 for uncompressed P2PKH keys, 31 to 34 for compressed ones, and
+/// following BIP137, 35 to 38 for P2SH-P2WPKH and 39 to 42 for P2WPKH.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageAddressType {
+    P2pkhUncompressed,
+    P2pkh,
+    P2shP2wpkh,
+    P2wpkh,
+}
+
+impl MessageAddressType {
+    /// Returns the header byte of the signatures of recovery id 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `electrum` - Whether to use the compressed P2PKH header for segwit addresses, as
+    ///   Electrum does, rather than the BIP137 ones used by Trezor.
+    pub fn header_base(&self, electrum: bool) -> u8 {
+        match (self, electrum) {
+            (MessageAddressType::P2pkhUncompressed, _) => 27,
+            (MessageAddressType::P2pkh, _) | (_, true) => 31,
+            (MessageAddressType::P2shP2wpkh, false) => 35,
+            (MessageAddressType::P2wpkh, false) => 39,
+        }
+    }
+
+    /// Returns the address of a public key, of the type.
+    pub fn address(&self, public_key: &PublicKey, network: Network) -> Address {
+        let hash = public_key.compressed.clone().hash160();
+
+        match self {
+            MessageAddressType::P2pkhUncompressed => {
+                Address::p2pkh(public_key.uncompressed.clone().hash160(), network)
+            }
+            MessageAddressType::P2pkh => Address::p2pkh(hash, network),
+            MessageAddressType::P2shP2wpkh => {
+                Address::p2sh([&[0x00, 0x14], &hash[..]].concat().hash160(), network)
+            }
+            MessageAddressType::P2wpkh => Address::segwit(0, hash, network),
+        }
+    }
+}
+
+impl FromStr for MessageAddressType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "p2pkh-uncompressed" => Ok(MessageAddressType::P2pkhUncompressed),
+            "p2pkh" | "legacy" => Ok(MessageAddressType::P2pkh),
+            "p2sh-p2wpkh" | "nested-segwit" => Ok(MessageAddressType::P2shP2wpkh),
+            "p2wpkh" | "segwit" => Ok(MessageAddressType::P2wpkh),
+            _ => Err(format!("unknown address type: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for MessageAddressType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MessageAddressType::P2pkhUncompressed => "p2pkh-uncompressed",
+            MessageAddressType::P2pkh => "p2pkh",
+            MessageAddressType::P2shP2wpkh => "p2sh-p2wpkh",
+            MessageAddressType::P2wpkh => "p2wpkh",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Returns the hash signed by legacy signed messages: the double SHA256 of the
+/// "Bitcoin Signed Message:\n" prefix and the message, both prefixed with their length.
+pub fn message_hash(message: &str) -> Vec<u8> {
+    let mut data = compact_size(MESSAGE_PREFIX.len() as u64);
+    data.extend_from_slice(MESSAGE_PREFIX.as_bytes());
+    data.extend(compact_size(message.len() as u64));
+    data.extend_from_slice(message.as_bytes());
+
+    data.sha256().sha256()
+}
+
+/// Signs a message with a private key, returning the base64 encoded recoverable signature.
+///
+/// # Arguments
+///
+/// * `private_key` - The key of the address the message is signed for.
+/// * `message` - The message, signed as is.
+/// * `address_type` - The type of the address, committed to by the header byte.
+/// * `electrum` - Whether to use the Electrum header bytes for segwit addresses.
+pub fn sign_message(
+    private_key: &PrivateKey,
+    message: &str,
+    address_type: MessageAddressType,
+    electrum: bool,
+) -> Result<String, MessageError> {
+    let secp = Secp256k1::signing_only();
+    let secret_key = SecretKey::from_slice(&private_key.key).map_err(MessageError::InvalidPrivateKey)?;
+    let hash = Message::from_slice(&message_hash(message)).expect("message hashes are 32 bytes");
+
+    let (recovery_id, signature) = secp.sign_ecdsa_recoverable(&hash, &secret_key).serialize_compact();
+
+    let mut bytes = vec![address_type.header_base(electrum) + recovery_id.to_i32() as u8];
+    bytes.extend_from_slice(&signature);
+
+    Ok(base64::encode(bytes))
+}
+
+/// Recovers the public key of a signature, along with the address type of its header.
+pub fn recover_public_key(
+    message: &str,
+    signature: &str,
+) -> Result<(PublicKey, MessageAddressType), MessageError> {
+    let bytes = base64::decode(signature)?;
+
+    if bytes.len() != 65 {
+        return Err(MessageError::InvalidSignatureLength(bytes.len()));
+    }
+
+    let address_type = match bytes[0] {
+        27..=30 => MessageAddressType::P2pkhUncompressed,
+        31..=34 => MessageAddressType::P2pkh,
+        35..=38 => MessageAddressType::P2shP2wpkh,
+        39..=42 => MessageAddressType::P2wpkh,
+        header => return Err(MessageError::InvalidHeader(header)),
+    };
+
+    let recovery_id = RecoveryId::from_i32(((bytes[0] - 27) % 4) as i32)?;
+    let signature = RecoverableSignature::from_compact(&bytes[1..], recovery_id)?;
+    let hash = Message::from_slice(&message_hash(message))?;
+
+    let public_key = Secp256k1::verification_only().recover_ecdsa(&hash, &signature)?;
+
+    Ok((PublicKey::from_secp256k1(public_key), address_type))
+}
+
+/// Verifies a signed message against an address.
+///
+/// Compressed P2PKH headers are accepted for segwit addresses, as Electrum uses them, while
+/// segwit headers must match the type of the address.
+///
+/// # Return
+///
+/// * Whether the key recovered from the signature is the one of the address.
+pub fn verify_message(address: &str, signature: &str, message: &str) -> Result<bool, MessageError> {
+    let address = Address::from_str(address)?;
+    let (public_key, header_type) = recover_public_key(message, signature)?;
+
+    let address_type = match (address.address_type, header_type) {
+        (AddressType::P2pkh, MessageAddressType::P2pkhUncompressed | MessageAddressType::P2pkh) => {
+            header_type
+        }
+        (AddressType::P2sh, MessageAddressType::P2pkh | MessageAddressType::P2shP2wpkh) => {
+            MessageAddressType::P2shP2wpkh
+        }
+        (AddressType::P2wpkh, MessageAddressType::P2pkh | MessageAddressType::P2wpkh) => {
+            MessageAddressType::P2wpkh
+        }
+        (AddressType::P2pkh | AddressType::P2sh | AddressType::P2wpkh, _) => return Ok(false),
+        (address_type, _) => return Err(MessageError::UnsupportedAddress(address_type)),
+    };
+
+    Ok(address_type.address(&public_key, address.network) == address)
+}
+
+#[cfg(test)]
+mod message_tests {
+    use super::*;
+    use crate::key::{
+        ADDRESS_FROM_COMPRESSED, ADDRESS_FROM_UNCOMPRESSED, P2SH_P2WPKH_ADDRESS, P2TR_ADDRESS,
+        P2WPKH_ADDRESS, PRIVATE_KEY,
+    };
+
+    const MESSAGE: &str = "Hello, World!";
+    const SIGNATURE: &str = "ESWxySuThtvciDVPW1019NPS/kF7bVlOwSSCEpVv68cfnnZY/c953z2DfH29o3H1mVcJe3vkBR+qMnMFY7M91Y=";
+
+    fn private_key() -> PrivateKey {
+        PrivateKey::from_str(PRIVATE_KEY).unwrap()
+    }
+
+    #[test]
+    fn should_hash_message_with_prefix() {
+        assert_eq!(
+            hex::encode(message_hash("")),
+            "80e795d4a4caadd7047af389d9f7f220562feb6196032e2131e10563352c4bcc"
+        );
+        assert_eq!(
+            hex::encode(message_hash(MESSAGE)),
+            "9c79d657e6ada65c9b3c62a3a040cecc8f7df65781cb6ee30bf4717f6f4e0655"
+        );
+    }
+
+    #[test]
+    fn should_sign_message_with_expected_header() {
+        let vectors = [
+            (MessageAddressType::P2pkhUncompressed, false, "H"),
+            (MessageAddressType::P2pkh, false, "I"),
+            (MessageAddressType::P2shP2wpkh, false, "J"),
+            (MessageAddressType::P2wpkh, false, "K"),
+            (MessageAddressType::P2wpkh, true, "I"),
+        ];
+
+        for (address_type, electrum, header) in vectors {
+            assert_eq!(
+                sign_message(&private_key(), MESSAGE, address_type, electrum),
+                Ok(format!("{}{}", header, SIGNATURE))
+            );
+        }
+    }
+
+    #[test]
+    fn should_throw_error_if_private_key_is_invalid() {
+        assert_eq!(
+            sign_message(&PrivateKey::from_str("0").unwrap(), MESSAGE, MessageAddressType::P2pkh, false),
+            Err(MessageError::InvalidPrivateKey(secp256k1::Error::InvalidSecretKey))
+        );
+    }
+
+    #[test]
+    fn should_verify_signed_messages() {
+        let vectors = [
+            (ADDRESS_FROM_UNCOMPRESSED, "H"),
+            (ADDRESS_FROM_COMPRESSED, "I"),
+            (P2SH_P2WPKH_ADDRESS, "J"),
+            (P2SH_P2WPKH_ADDRESS, "I"),
+            (P2WPKH_ADDRESS, "K"),
+            (P2WPKH_ADDRESS, "I"),
+        ];
+
+        for (address, header) in vectors {
+            let signature = format!("{}{}", header, SIGNATURE);
+
+            assert_eq!(verify_message(address, &signature, MESSAGE), Ok(true));
+            assert_eq!(verify_message(address, &signature, "Hello, World?"), Ok(false));
+        }
+    }
+
+    #[test]
+    fn should_not_verify_mismatched_header() {
+        let signature = format!("K{}", SIGNATURE);
+
+        assert_eq!(verify_message(ADDRESS_FROM_COMPRESSED, &signature, MESSAGE), Ok(false));
+        assert_eq!(verify_message(P2SH_P2WPKH_ADDRESS, &signature, MESSAGE), Ok(false));
+    }
+
+    #[test]
+    fn should_throw_error_if_signature_is_invalid() {
+        assert_eq!(
+            verify_message(ADDRESS_FROM_COMPRESSED, &format!("A{}", SIGNATURE), MESSAGE),
+            Err(MessageError::InvalidHeader(0x00))
+        );
+        assert_eq!(
+            verify_message(ADDRESS_FROM_COMPRESSED, "SGVsbG8=", MESSAGE),
+            Err(MessageError::InvalidSignatureLength(5))
+        );
+        assert!(matches!(
+            verify_message(ADDRESS_FROM_COMPRESSED, "not base64!", MESSAGE),
+            Err(MessageError::InvalidBase64(_))
+        ));
+        assert_eq!(
+            verify_message(P2TR_ADDRESS, &format!("I{}", SIGNATURE), MESSAGE),
+            Err(MessageError::UnsupportedAddress(AddressType::P2tr))
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// The languages of the official BIP39 wordlists.
+///
+/// The wordlists are stored NFKD normalized, the form words are compared and hashed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+    Korean,
+    Spanish,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Czech,
+    Portuguese,
+}
+
+impl Language {
+    pub fn all() -> [Language; 10] {
+        [
+            Language::English,
+            Language::Japanese,
+            Language::Korean,
+            Language::Spanish,
+            Language::ChineseSimplified,
+            Language::ChineseTraditional,
+            Language::French,
+            Language::Italian,
+            Language::Czech,
+            Language::Portuguese,
+        ]
+    }
+
+    /// Returns the 2048 words of the language, in index order.
+    pub fn wordlist(&self) -> Vec<&'static str> {
+        let words = match self {
+            Language::English => include_str!("wordlists/english.txt"),
+            Language::Japanese => include_str!("wordlists/japanese.txt"),
+            Language::Korean => include_str!("wordlists/korean.txt"),
+            Language::Spanish => include_str!("wordlists/spanish.txt"),
+            Language::ChineseSimplified => include_str!("wordlists/chinese_simplified.txt"),
+            Language::ChineseTraditional => include_str!("wordlists/chinese_traditional.txt"),
+            Language::French => include_str!("wordlists/french.txt"),
+            Language::Italian => include_str!("wordlists/italian.txt"),
+            Language::Czech => include_str!("wordlists/czech.txt"),
+            Language::Portuguese => include_str!("wordlists/portuguese.txt"),
+        };
+
+        words.lines().collect()
+    }
+
+    /// Returns the separator of the words of a phrase, an ideographic space for Japanese.
+    pub fn separator(&self) -> &'static str {
+        match self {
+            Language::Japanese => "\u{3000}",
+            _ => " ",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_'], " ").as_str() {
+            "english" | "en" => Ok(Language::English),
+            "japanese" | "ja" => Ok(Language::Japanese),
+            "korean" | "ko" => Ok(Language::Korean),
+            "spanish" | "es" => Ok(Language::Spanish),
+            "chinese simplified" | "zh hans" => Ok(Language::ChineseSimplified),
+            "chinese traditional" | "zh hant" => Ok(Language::ChineseTraditional),
+            "french" | "fr" => Ok(Language::French),
+            "italian" | "it" => Ok(Language::Italian),
+            "czech" | "cs" => Ok(Language::Czech),
+            "portuguese" | "pt" => Ok(Language::Portuguese),
+            _ => Err(format!("unknown language: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Language::English => "english",
+            Language::Japanese => "japanese",
+            Language::Korean => "korean",
+            Language::Spanish => "spanish",
+            Language::ChineseSimplified => "chinese-simplified",
+            Language::ChineseTraditional => "chinese-traditional",
+            Language::French => "french",
+            Language::Italian => "italian",
+            Language::Czech => "czech",
+            Language::Portuguese => "portuguese",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod language_tests {
+    use super::*;
+    use unicode_normalization::UnicodeNormalization;
+
+    #[test]
+    fn should_have_2048_nfkd_normalized_words_per_language() {
+        for language in Language::all() {
+            let wordlist = language.wordlist();
+
+            assert_eq!(wordlist.len(), 2048);
+            assert!(wordlist.iter().all(|word| word.nfkd().collect::<String>() == *word));
+        }
+    }
+
+    #[test]
+    fn should_parse_language_names() {
+        for language in Language::all() {
+            assert_eq!(Language::from_str(&language.to_string()), Ok(language));
+        }
+
+        assert_eq!(Language::from_str("Chinese_Simplified"), Ok(Language::ChineseSimplified));
+        assert!(Language::from_str("klingon").is_err());
+    }
+}
@@ -0,0 +1,345 @@
+use std::fmt;
+
+use crypto::{hmac::Hmac, pbkdf2::pbkdf2, sha2::Sha512};
+use secp256k1::rand::{self, RngCore};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::key::{ExtendedKeyError, ExtendedPrivateKey, Key};
+use crate::network::Network;
+
+mod language;
+pub use language::Language;
+
+/// Number of PBKDF2 rounds used to stretch a mnemonic into a seed.
+const PBKDF2_ROUNDS: u32 = 2048;
+
+#[derive(Debug, PartialEq)]
+pub enum MnemonicError {
+    /// Mnemonics are 12, 15, 18, 21 or 24 words long.
+    InvalidWordCount(usize),
+    /// Entropy is 16, 20, 24, 28 or 32 bytes long.
+    InvalidEntropyLength(usize),
+    UnknownWord(String),
+    /// Every word is in a wordlist, but no single wordlist has them all.
+    UnknownLanguage,
+    InvalidChecksum,
+}
+
+/// A BIP39 mnemonic phrase, encoding 128 to 256 bits of entropy along with a checksum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mnemonic {
+    /// The words, NFKD normalized as in the wordlist.
+    pub words: Vec<String>,
+    pub language: Language,
+}
+
+impl Mnemonic {
+    /// Generates a mnemonic of the given number of words from random entropy.
+    ///
+    /// # Arguments
+    ///
+    /// * `word_count` - 12, 15, 18, 21 or 24, for 128 to 256 bits of entropy.
+    /// * `language` - The language of the wordlist to pick the words from.
+    pub fn generate(word_count: usize, language: Language) -> Result<Self, MnemonicError> {
+        if !is_valid_word_count(word_count) {
+            return Err(MnemonicError::InvalidWordCount(word_count));
+        }
+
+        let mut entropy = vec![0x00; word_count * 4 / 3];
+        rand::thread_rng().fill_bytes(&mut entropy);
+
+        Mnemonic::from_entropy(&entropy, language)
+    }
+
+    /// Returns the mnemonic encoding the given entropy.
+    ///
+    /// The entropy is followed by the first `length / 32` bits of its SHA256 as a checksum, and
+    /// every group of 11 bits is mapped to a word of the wordlist.
+    pub fn from_entropy(entropy: &[u8], language: Language) -> Result<Self, MnemonicError> {
+        if entropy.len() < 16 || entropy.len() > 32 || !entropy.len().is_multiple_of(4) {
+            return Err(MnemonicError::InvalidEntropyLength(entropy.len()));
+        }
+
+        let checksum_bits = entropy.len() / 4;
+        let mut data = entropy.to_vec();
+        data.push(entropy.to_vec().sha256()[0]);
+
+        let wordlist = language.wordlist();
+        let words = to_bits(&data)
+            .into_iter()
+            .take(entropy.len() * 8 + checksum_bits)
+            .collect::<Vec<bool>>()
+            .chunks(11)
+            .map(|chunk| wordlist[from_bits(chunk)].to_string())
+            .collect();
+
+        Ok(Mnemonic { words, language })
+    }
+
+    /// Parses a mnemonic phrase, detecting its language and verifying its words and checksum.
+    ///
+    /// Some words are shared by several wordlists, e.g. English and French or the two Chinese
+    /// ones, so the phrase is read in the first language whose wordlist has every word and
+    /// whose checksum matches.
+    pub fn from_phrase(phrase: &str) -> Result<Self, MnemonicError> {
+        let words = normalize_words(phrase);
+
+        if !is_valid_word_count(words.len()) {
+            return Err(MnemonicError::InvalidWordCount(words.len()));
+        }
+
+        let candidates: Vec<Language> = Language::all()
+            .into_iter()
+            .filter(|language| {
+                let wordlist = language.wordlist();
+                words.iter().all(|word| wordlist.contains(&word.as_str()))
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            let unknown = words.iter().find(|word| {
+                Language::all().iter().all(|language| !language.wordlist().contains(&word.as_str()))
+            });
+
+            return match unknown {
+                Some(word) => Err(MnemonicError::UnknownWord(word.to_string())),
+                None => Err(MnemonicError::UnknownLanguage),
+            };
+        }
+
+        let mut results = candidates.into_iter().map(|language| Mnemonic::from_phrase_in(phrase, language));
+        let first = results.next().unwrap();
+
+        match first {
+            Ok(mnemonic) => Ok(mnemonic),
+            Err(error) => results.find(|r| r.is_ok()).unwrap_or(Err(error)),
+        }
+    }
+
+    /// Parses a mnemonic phrase of the given language, verifying its words and checksum.
+    pub fn from_phrase_in(phrase: &str, language: Language) -> Result<Self, MnemonicError> {
+        let words = normalize_words(phrase);
+
+        if !is_valid_word_count(words.len()) {
+            return Err(MnemonicError::InvalidWordCount(words.len()));
+        }
+
+        let mnemonic = Mnemonic { words, language };
+        let entropy = mnemonic.entropy()?;
+
+        match Mnemonic::from_entropy(&entropy, language)? == mnemonic {
+            true => Ok(mnemonic),
+            false => Err(MnemonicError::InvalidChecksum),
+        }
+    }
+
+    /// Returns the entropy encoded by the mnemonic, without its checksum.
+    pub fn entropy(&self) -> Result<Vec<u8>, MnemonicError> {
+        let wordlist = self.language.wordlist();
+        let mut bits = Vec::new();
+
+        for word in &self.words {
+            let index = wordlist
+                .iter()
+                .position(|w| w == word)
+                .ok_or_else(|| MnemonicError::UnknownWord(word.to_string()))?;
+
+            bits.extend((0..11).rev().map(|i| index >> i & 1 == 1));
+        }
+
+        let entropy_bits = bits.len() * 32 / 33;
+
+        Ok(bits[..entropy_bits].chunks(8).map(|byte| from_bits(byte) as u8).collect())
+    }
+
+    /// Returns the 64-byte seed of the mnemonic: PBKDF2-HMAC-SHA512 of the phrase, salted with
+    /// "mnemonic" followed by the passphrase, both NFKD normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `passphrase` - An optional extra word, empty if unused.
+    pub fn to_seed(&self, passphrase: &str) -> Vec<u8> {
+        let mut seed = vec![0x00; 64];
+        let phrase = self.to_string().nfkd().collect::<String>();
+        let mut mac = Hmac::new(Sha512::new(), phrase.as_bytes());
+        let salt = format!("mnemonic{}", passphrase).nfkd().collect::<String>();
+
+        pbkdf2(&mut mac, salt.as_bytes(), PBKDF2_ROUNDS, &mut seed);
+
+        seed
+    }
+
+    /// Returns the BIP32 master key of the seed of the mnemonic.
+    pub fn to_extended_private_key(
+        &self,
+        passphrase: &str,
+        network: Network,
+    ) -> Result<ExtendedPrivateKey, ExtendedKeyError> {
+        ExtendedPrivateKey::from_seed(&self.to_seed(passphrase), network)
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.words.join(self.language.separator()))
+    }
+}
+
+fn normalize_words(phrase: &str) -> Vec<String> {
+    phrase
+        .split_whitespace()
+        .map(|word| word.to_lowercase().nfkd().collect())
+        .collect()
+}
+
+fn is_valid_word_count(word_count: usize) -> bool {
+    (12..=24).contains(&word_count) && word_count.is_multiple_of(3)
+}
+
+fn to_bits(data: &[u8]) -> Vec<bool> {
+    data.iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1 == 1))
+        .collect()
+}
+
+fn from_bits(bits: &[bool]) -> usize {
+    bits.iter().fold(0, |value, bit| value << 1 | *bit as usize)
+}
+
+#[cfg(test)]
+mod mnemonic_tests {
+    use super::*;
+
+    const ABANDON_ABOUT: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn should_encode_bip39_test_vectors() {
+        let vectors = [
+            ("00000000000000000000000000000000", ABANDON_ABOUT),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            ),
+            (
+                "8080808080808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor accident",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+            ),
+        ];
+
+        for (entropy, phrase) in vectors {
+            let mnemonic = Mnemonic::from_entropy(&hex::decode(entropy).unwrap(), Language::English).unwrap();
+
+            assert_eq!(mnemonic.to_string(), phrase);
+            assert_eq!(hex::encode(Mnemonic::from_phrase(phrase).unwrap().entropy().unwrap()), entropy);
+        }
+    }
+
+    #[test]
+    fn should_return_bip39_test_vector_seeds() {
+        let mnemonic = Mnemonic::from_phrase(ABANDON_ABOUT).unwrap();
+
+        assert_eq!(
+            hex::encode(mnemonic.to_seed("TREZOR")),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        assert_eq!(
+            hex::encode(mnemonic.to_seed("")),
+            "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4"
+        );
+    }
+
+    #[test]
+    fn should_return_master_key_of_seed() {
+        let mnemonic = Mnemonic::from_phrase(ABANDON_ABOUT).unwrap();
+
+        assert_eq!(
+            mnemonic.to_extended_private_key("", Network::Mainnet).unwrap().to_string(),
+            "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu"
+        );
+    }
+
+    #[test]
+    fn should_generate_valid_mnemonics() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let mnemonic = Mnemonic::generate(word_count, Language::English).unwrap();
+
+            assert_eq!(mnemonic.words.len(), word_count);
+            assert_eq!(Mnemonic::from_phrase(&mnemonic.to_string()).unwrap(), mnemonic);
+        }
+    }
+
+    #[test]
+    fn should_throw_error_if_mnemonic_is_invalid() {
+        assert_eq!(Mnemonic::generate(13, Language::English), Err(MnemonicError::InvalidWordCount(13)));
+        assert_eq!(Mnemonic::from_entropy(&[0x00; 15], Language::English), Err(MnemonicError::InvalidEntropyLength(15)));
+        assert_eq!(
+            Mnemonic::from_phrase(&ABANDON_ABOUT.replace("about", "abandon")),
+            Err(MnemonicError::InvalidChecksum)
+        );
+        assert_eq!(
+            Mnemonic::from_phrase(&ABANDON_ABOUT.replace("about", "bitcoin")),
+            Err(MnemonicError::UnknownWord("bitcoin".to_string()))
+        );
+        assert_eq!(
+            Mnemonic::from_phrase("abandon about"),
+            Err(MnemonicError::InvalidWordCount(2))
+        );
+        assert_eq!(
+            Mnemonic::from_phrase(&ABANDON_ABOUT.replace("about", "ligero")),
+            Err(MnemonicError::UnknownLanguage)
+        );
+    }
+
+    #[test]
+    fn should_return_bip39_japanese_test_vector_seed() {
+        let phrase = "あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あおぞら";
+        let mnemonic = Mnemonic::from_entropy(&[0x00; 16], Language::Japanese).unwrap();
+
+        assert_eq!(mnemonic.to_string().nfc().collect::<String>(), phrase);
+        assert_eq!(Mnemonic::from_phrase(phrase).unwrap(), mnemonic);
+        assert_eq!(
+            hex::encode(mnemonic.to_seed("㍍ガバヴァぱばぐゞちぢ十人十色")),
+            "a262d6fb6122ecf45be09c50492b31f92e9beb7d9a845987a02cefda57a15f9c467a17872029a9e92299b5cbdf306e3a0ee620245cbd508959b6cb7ca637bd55"
+        );
+    }
+
+    #[test]
+    fn should_detect_language_of_phrase() {
+        let vectors = [
+            (Language::Spanish, "ligero vista talar yogur venta queso yacer trozo ligero vista talar zafiro"),
+            (Language::French, "implorer visage sonnette voyage véloce pourpre volaille tribunal implorer visage sonnette voyelle"),
+            (Language::Italian, "mimosa vita sussurro zinco vero saltare zattera ulisse mimosa vita sussurro zircone"),
+            (Language::Czech, "obrazec znak uznat zubovina zeman skupina zrcadlo vzchopit obrazec znak uznat zubr"),
+            (Language::Portuguese, "imitador vinheta sogro xerife veleiro pomar volumoso tratador imitador vinheta sogro xingar"),
+            (Language::Korean, "실장 활동 큰절 흔적 형제 제대로 훈련 한글 실장 활동 큰절 흔히"),
+            (Language::ChineseSimplified, "枪 疫 霉 尝 俩 闹 饿 贤 枪 疫 霉 卿"),
+            (Language::ChineseTraditional, "槍 疫 黴 嘗 倆 鬧 餓 賢 槍 疫 黴 卿"),
+        ];
+
+        for (language, phrase) in vectors {
+            let mnemonic = Mnemonic::from_phrase(phrase).unwrap();
+
+            assert_eq!(mnemonic.language, language);
+            assert_eq!(hex::encode(mnemonic.entropy().unwrap()), "7f".repeat(16));
+        }
+    }
+
+    #[test]
+    fn should_normalize_phrase_before_hashing() {
+        let composed = "implorer visage sonnette voyage v\u{e9}loce pourpre volaille tribunal implorer visage sonnette voyelle";
+        let decomposed = "implorer visage sonnette voyage ve\u{301}loce pourpre volaille tribunal implorer visage sonnette voyelle";
+
+        assert_eq!(
+            Mnemonic::from_phrase(composed).unwrap().to_seed("TREZOR"),
+            Mnemonic::from_phrase(decomposed).unwrap().to_seed("TREZOR")
+        );
+        assert_eq!(
+            hex::encode(&Mnemonic::from_phrase(composed).unwrap().to_seed("TREZOR")[..16]),
+            "ab9180b7dfdde74e5cf8781e5692e2c0"
+        );
+    }
+}
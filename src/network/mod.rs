@@ -0,0 +1,140 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// The Bitcoin networks btcli knows how to encode keys and addresses for.
+///
+/// Each network defines the version bytes prepended to Base58Check payloads and the
+/// human-readable part used by bech32 addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    /// Returns every supported network.
+    pub fn all() -> [Network; 4] {
+        [
+            Network::Mainnet,
+            Network::Testnet,
+            Network::Signet,
+            Network::Regtest,
+        ]
+    }
+
+    /// Returns the version byte of P2PKH addresses.
+    pub fn p2pkh_version(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x00,
+            _ => 0x6f,
+        }
+    }
+
+    /// Returns the version byte of P2SH addresses.
+    pub fn p2sh_version(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x05,
+            _ => 0xc4,
+        }
+    }
+
+    /// Returns the version byte of private keys in the "Wallet Import Format".
+    pub fn wif_version(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x80,
+            _ => 0xef,
+        }
+    }
+
+    /// Returns the human-readable part of segwit addresses.
+    pub fn hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet | Network::Signet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+
+    /// Returns the first network whose WIF version byte matches `version`.
+    ///
+    /// Testnet, signet and regtest share their version bytes, so testnet stands for all three.
+    pub fn from_wif_version(version: u8) -> Option<Network> {
+        Network::all()
+            .into_iter()
+            .find(|network| network.wif_version() == version)
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "main" | "bitcoin" => Ok(Network::Mainnet),
+            "testnet" | "test" => Ok(Network::Testnet),
+            "signet" => Ok(Network::Signet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(format!("unknown network: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Signet => "signet",
+            Network::Regtest => "regtest",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod network_tests {
+    use super::Network;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_return_expected_version_bytes() {
+        assert_eq!(Network::Mainnet.p2pkh_version(), 0x00);
+        assert_eq!(Network::Mainnet.p2sh_version(), 0x05);
+        assert_eq!(Network::Mainnet.wif_version(), 0x80);
+
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            assert_eq!(network.p2pkh_version(), 0x6f);
+            assert_eq!(network.p2sh_version(), 0xc4);
+            assert_eq!(network.wif_version(), 0xef);
+        }
+    }
+
+    #[test]
+    fn should_return_expected_hrp() {
+        assert_eq!(Network::Mainnet.hrp(), "bc");
+        assert_eq!(Network::Testnet.hrp(), "tb");
+        assert_eq!(Network::Signet.hrp(), "tb");
+        assert_eq!(Network::Regtest.hrp(), "bcrt");
+    }
+
+    #[test]
+    fn should_parse_network_names() {
+        for network in Network::all() {
+            assert_eq!(Network::from_str(&network.to_string()), Ok(network));
+        }
+
+        assert_eq!(Network::from_str("Bitcoin"), Ok(Network::Mainnet));
+        assert!(Network::from_str("litecoin").is_err());
+    }
+
+    #[test]
+    fn should_find_network_from_wif_version() {
+        assert_eq!(Network::from_wif_version(0x80), Some(Network::Mainnet));
+        assert_eq!(Network::from_wif_version(0xef), Some(Network::Testnet));
+        assert_eq!(Network::from_wif_version(0x81), None);
+    }
+}
@@ -0,0 +1,317 @@
+pub mod opcodes;
+
+use opcodes::{opcode_from_name, opcode_name, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4};
+
+#[derive(Debug, PartialEq)]
+pub enum ScriptError {
+    InvalidHex(hex::FromHexError),
+    /// A push announced more bytes than the script has left.
+    UnexpectedEnd,
+    UnknownToken(String),
+    /// A `OP_PUSHDATA` opcode was not followed by the data to push, or the data does not fit.
+    InvalidPush(String),
+}
+
+impl From<hex::FromHexError> for ScriptError {
+    fn from(err: hex::FromHexError) -> Self {
+        ScriptError::InvalidHex(err)
+    }
+}
+
+/// A single operation of a script.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    /// Pushes `data` onto the stack, using `opcode` to encode its length.
+    Push { opcode: u8, data: Vec<u8> },
+    Op(u8),
+}
+
+/// A Bitcoin script, kept as its serialized bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub bytes: Vec<u8>,
+}
+
+impl Script {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Script { bytes }
+    }
+
+    /// Returns a script given its serialization as a hex string.
+    pub fn from_hex(script: &str) -> Result<Self, ScriptError> {
+        Ok(Script::new(hex::decode(script)?))
+    }
+
+    /// Assembles a script from its ASM representation.
+    ///
+    /// Tokens are either opcode names, with or without the `OP_` prefix, or hex strings which
+    /// are pushed with the smallest push opcode that fits them. Tokens without the prefix are
+    /// read as hex first, so that `10` is a push as disassembled rather than `OP_10`. A
+    /// `OP_PUSHDATA1`, `OP_PUSHDATA2` or `OP_PUSHDATA4` token forces the encoding of the hex
+    /// string that follows it.
+    pub fn from_asm(asm: &str) -> Result<Self, ScriptError> {
+        let mut bytes = Vec::new();
+        let mut tokens = asm.split_whitespace();
+
+        while let Some(token) = tokens.next() {
+            let is_name = token.to_uppercase().starts_with("OP_") || hex::decode(token).is_err();
+            let opcode = if is_name { opcode_from_name(token) } else { None };
+
+            match opcode {
+                Some(opcode) if matches!(opcode, OP_PUSHDATA1 | OP_PUSHDATA2 | OP_PUSHDATA4) => {
+                    let data = tokens
+                        .next()
+                        .and_then(|data| hex::decode(data).ok())
+                        .ok_or_else(|| ScriptError::InvalidPush(token.to_string()))?;
+
+                    push_data(&mut bytes, opcode, &data)?;
+                }
+                Some(opcode) => bytes.push(opcode),
+                None => match parse_unknown_opcode(token) {
+                    Some(opcode) => bytes.push(opcode),
+                    None => {
+                        let data = hex::decode(token)
+                            .map_err(|_| ScriptError::UnknownToken(token.to_string()))?;
+
+                        push_data(&mut bytes, minimal_push_opcode(data.len()), &data)?;
+                    }
+                },
+            }
+        }
+
+        Ok(Script::new(bytes))
+    }
+
+    /// Returns the instructions of the script, or an error if a push runs past its end.
+    pub fn instructions(&self) -> Result<Vec<Instruction>, ScriptError> {
+        let mut instructions = Vec::new();
+        let mut cursor = 0;
+
+        while cursor < self.bytes.len() {
+            let opcode = self.bytes[cursor];
+            cursor += 1;
+
+            let (length_size, length) = match opcode {
+                0x01..=0x4b => (0, opcode as usize),
+                OP_PUSHDATA1 => (1, self.read_length(cursor, 1)?),
+                OP_PUSHDATA2 => (2, self.read_length(cursor, 2)?),
+                OP_PUSHDATA4 => (4, self.read_length(cursor, 4)?),
+                _ => {
+                    instructions.push(Instruction::Op(opcode));
+                    continue;
+                }
+            };
+
+            cursor += length_size;
+
+            if cursor + length > self.bytes.len() {
+                return Err(ScriptError::UnexpectedEnd);
+            }
+
+            instructions.push(Instruction::Push {
+                opcode,
+                data: self.bytes[cursor..cursor + length].to_vec(),
+            });
+            cursor += length;
+        }
+
+        Ok(instructions)
+    }
+
+    fn read_length(&self, cursor: usize, size: usize) -> Result<usize, ScriptError> {
+        if cursor + size > self.bytes.len() {
+            return Err(ScriptError::UnexpectedEnd);
+        }
+
+        let length = self.bytes[cursor..cursor + size]
+            .iter()
+            .rev()
+            .fold(0, |length, byte| (length << 8) | *byte as usize);
+
+        Ok(length)
+    }
+
+    /// Returns the ASM representation of the script.
+    ///
+    /// Pushes are shown as hex, prefixed by their opcode when it is one of the `OP_PUSHDATA`
+    /// ones. Malformed scripts are disassembled up to the failing push, followed by `[error]`.
+    pub fn to_asm(&self) -> String {
+        let instructions = self.instructions();
+        let mut tokens = Vec::new();
+
+        let valid = match &instructions {
+            Ok(instructions) => instructions.clone(),
+            Err(_) => self.valid_prefix(),
+        };
+
+        for instruction in valid {
+            match instruction {
+                Instruction::Push { opcode, data } => {
+                    if matches!(opcode, OP_PUSHDATA1 | OP_PUSHDATA2 | OP_PUSHDATA4) {
+                        tokens.push(opcode_name(opcode).unwrap().to_string());
+                    }
+                    tokens.push(hex::encode(data));
+                }
+                Instruction::Op(opcode) => tokens.push(match opcode_name(opcode) {
+                    Some(name) => name.to_string(),
+                    None => format!("OP_UNKNOWN_0x{:02x}", opcode),
+                }),
+            }
+        }
+
+        if instructions.is_err() {
+            tokens.push("[error]".to_string());
+        }
+
+        tokens.join(" ")
+    }
+
+    fn valid_prefix(&self) -> Vec<Instruction> {
+        (0..self.bytes.len())
+            .rev()
+            .find_map(|end| Script::new(self.bytes[..end].to_vec()).instructions().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn as_hex_string(&self) -> String {
+        hex::encode(&self.bytes)
+    }
+}
+
+/// Returns the smallest opcode able to push `length` bytes.
+pub fn minimal_push_opcode(length: usize) -> u8 {
+    match length {
+        0..=0x4b => length as u8,
+        0x4c..=0xff => OP_PUSHDATA1,
+        0x100..=0xffff => OP_PUSHDATA2,
+        _ => OP_PUSHDATA4,
+    }
+}
+
+/// Appends the serialization of a push of `data` using `opcode` to `bytes`.
+pub fn push_data(bytes: &mut Vec<u8>, opcode: u8, data: &[u8]) -> Result<(), ScriptError> {
+    let length = data.len();
+
+    match opcode {
+        0x00..=0x4b if length == opcode as usize => bytes.push(opcode),
+        OP_PUSHDATA1 if length <= 0xff => {
+            bytes.push(opcode);
+            bytes.push(length as u8);
+        }
+        OP_PUSHDATA2 if length <= 0xffff => {
+            bytes.push(opcode);
+            bytes.extend_from_slice(&(length as u16).to_le_bytes());
+        }
+        OP_PUSHDATA4 if length <= 0xffffffff => {
+            bytes.push(opcode);
+            bytes.extend_from_slice(&(length as u32).to_le_bytes());
+        }
+        _ => return Err(ScriptError::InvalidPush(hex::encode(data))),
+    }
+
+    bytes.extend_from_slice(data);
+
+    Ok(())
+}
+
+fn parse_unknown_opcode(token: &str) -> Option<u8> {
+    token
+        .strip_prefix("OP_UNKNOWN_0x")
+        .and_then(|value| u8::from_str_radix(value, 16).ok())
+}
+
+#[cfg(test)]
+mod script_tests {
+    use super::*;
+
+    const P2PKH_HEX: &str = "76a914bbc1e42a39d05a4cc61752d6963b7f69d09bb27b88ac";
+    const P2PKH_ASM: &str =
+        "OP_DUP OP_HASH160 bbc1e42a39d05a4cc61752d6963b7f69d09bb27b OP_EQUALVERIFY OP_CHECKSIG";
+
+    #[test]
+    fn should_disassemble_p2pkh_script() {
+        assert_eq!(Script::from_hex(P2PKH_HEX).unwrap().to_asm(), P2PKH_ASM);
+    }
+
+    #[test]
+    fn should_assemble_p2pkh_script() {
+        assert_eq!(Script::from_asm(P2PKH_ASM).unwrap().as_hex_string(), P2PKH_HEX);
+    }
+
+    #[test]
+    fn should_disassemble_segwit_scripts() {
+        assert_eq!(
+            Script::from_hex("0014bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap().to_asm(),
+            "OP_0 bbc1e42a39d05a4cc61752d6963b7f69d09bb27b"
+        );
+        assert_eq!(
+            Script::from_hex("51208632f065149f924f7f78bee082cc19f81e5227f7526127a98a97db924e1a102c").unwrap().to_asm(),
+            "OP_1 8632f065149f924f7f78bee082cc19f81e5227f7526127a98a97db924e1a102c"
+        );
+    }
+
+    #[test]
+    fn should_use_minimal_push_encodings() {
+        let data = "ab".repeat(76);
+        let script = Script::from_asm(&data).unwrap();
+        assert_eq!(&script.as_hex_string()[..4], "4c4c");
+
+        let data = "ab".repeat(256);
+        let script = Script::from_asm(&data).unwrap();
+        assert_eq!(&script.as_hex_string()[..6], "4d0001");
+
+        let script = Script::from_asm("ab".repeat(75).as_str()).unwrap();
+        assert_eq!(&script.as_hex_string()[..2], "4b");
+    }
+
+    #[test]
+    fn should_round_trip_single_byte_pushes() {
+        for byte in 0x00..=0xff {
+            let script = Script::new(vec![0x01, byte]);
+            let asm = script.to_asm();
+
+            assert_eq!(Script::from_asm(&asm), Ok(script), "{}", asm);
+        }
+
+        assert_eq!(Script::from_hex("0110").unwrap().to_asm(), "10");
+        assert_eq!(Script::from_asm("10 OP_10 dup").unwrap().as_hex_string(), "01105a76");
+    }
+
+    #[test]
+    fn should_round_trip_non_minimal_pushes() {
+        let hex = "4c02abcd4e01000000ef";
+        let script = Script::from_hex(hex).unwrap();
+
+        assert_eq!(script.to_asm(), "OP_PUSHDATA1 abcd OP_PUSHDATA4 ef");
+        assert_eq!(Script::from_asm(&script.to_asm()).unwrap().as_hex_string(), hex);
+    }
+
+    #[test]
+    fn should_round_trip_unknown_opcodes() {
+        let script = Script::from_hex("bb51").unwrap();
+
+        assert_eq!(script.to_asm(), "OP_UNKNOWN_0xbb OP_1");
+        assert_eq!(Script::from_asm(&script.to_asm()).unwrap(), script);
+    }
+
+    #[test]
+    fn should_mark_truncated_pushes_as_errors() {
+        let script = Script::from_hex("76a914bbc1e4").unwrap();
+
+        assert_eq!(script.instructions(), Err(ScriptError::UnexpectedEnd));
+        assert_eq!(script.to_asm(), "OP_DUP OP_HASH160 [error]");
+        assert_eq!(Script::from_hex("4d01").unwrap().instructions(), Err(ScriptError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn should_throw_error_if_asm_is_invalid() {
+        assert_eq!(
+            Script::from_asm("OP_DUP OP_FOO"),
+            Err(ScriptError::UnknownToken("OP_FOO".to_string()))
+        );
+        assert_eq!(
+            Script::from_asm("OP_PUSHDATA1"),
+            Err(ScriptError::InvalidPush("OP_PUSHDATA1".to_string()))
+        );
+    }
+}
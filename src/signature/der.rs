@@ -0,0 +1,215 @@
+const SEQUENCE_TAG: u8 = 0x30;
+const INTEGER_TAG: u8 = 0x02;
+
+/// The order of the Secp256k1 curve divided by two, the highest low-S value.
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+#[derive(Debug, PartialEq)]
+pub enum DerError {
+    /// Strict DER signatures are 8 to 72 bytes long, or 9 to 73 with a sighash byte.
+    InvalidLength(usize),
+    /// The signature does not start with a SEQUENCE tag (0x30).
+    InvalidSequenceTag(u8),
+    /// The lengths encoded do not add up to the length of the signature.
+    LengthMismatch,
+    /// R or S is not tagged as an INTEGER (0x02).
+    InvalidIntegerTag(u8),
+    ZeroLengthInteger,
+    NegativeInteger,
+    /// R or S starts with a zero byte that is not needed to keep it positive.
+    NonMinimalInteger,
+    /// R or S is wider than the 32 bytes of the curve order.
+    IntegerTooLarge,
+}
+
+/// An ECDSA signature as found in scriptSigs and witnesses: strict DER, as enforced by BIP66,
+/// optionally followed by a sighash byte.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerSignature {
+    /// R as a 32-byte big endian integer.
+    pub r: Vec<u8>,
+    /// S as a 32-byte big endian integer.
+    pub s: Vec<u8>,
+    pub sighash_type: Option<u8>,
+}
+
+impl DerSignature {
+    /// Decodes and validates a strict DER signature, followed by a sighash byte or not.
+    ///
+    /// The sighash byte is detected from the length of the sequence: a signature is taken to
+    /// have one if its sequence stops one byte short of its end.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DerError> {
+        if bytes.len() < 8 || bytes.len() > 73 {
+            return Err(DerError::InvalidLength(bytes.len()));
+        }
+
+        if bytes[0] != SEQUENCE_TAG {
+            return Err(DerError::InvalidSequenceTag(bytes[0]));
+        }
+
+        let (signature, sighash_type) = match bytes[1] as usize + 2 {
+            length if length == bytes.len() && length <= 72 => (bytes, None),
+            length if length + 1 == bytes.len() && length >= 8 => (&bytes[..length], Some(bytes[length])),
+            _ => return Err(DerError::LengthMismatch),
+        };
+
+        let (r, rest) = decode_integer(&signature[2..])?;
+        let (s, rest) = decode_integer(rest)?;
+
+        if !rest.is_empty() {
+            return Err(DerError::LengthMismatch);
+        }
+
+        Ok(DerSignature { r, s, sighash_type })
+    }
+
+    /// Encodes the signature in strict DER, followed by its sighash byte if any.
+    pub fn encode(&self) -> Vec<u8> {
+        let r = encode_integer(&self.r);
+        let s = encode_integer(&self.s);
+
+        let mut bytes = vec![SEQUENCE_TAG, (r.len() + s.len()) as u8];
+        bytes.extend(r);
+        bytes.extend(s);
+        bytes.extend(self.sighash_type);
+        bytes
+    }
+
+    /// Returns the 64-byte compact encoding: R followed by S.
+    pub fn compact(&self) -> Vec<u8> {
+        [&self.r[..], &self.s[..]].concat()
+    }
+
+    /// Returns whether S is at most half the curve order, as required by standardness rules.
+    pub fn is_low_s(&self) -> bool {
+        self.s[..] <= HALF_ORDER[..]
+    }
+}
+
+/// Decodes an INTEGER, returning it left padded to 32 bytes along with the bytes after it.
+fn decode_integer(bytes: &[u8]) -> Result<(Vec<u8>, &[u8]), DerError> {
+    let (tag, length) = match bytes {
+        [tag, length, ..] => (*tag, *length as usize),
+        _ => return Err(DerError::LengthMismatch),
+    };
+
+    if tag != INTEGER_TAG {
+        return Err(DerError::InvalidIntegerTag(tag));
+    }
+
+    let integer = bytes.get(2..2 + length).ok_or(DerError::LengthMismatch)?;
+
+    match integer {
+        [] => return Err(DerError::ZeroLengthInteger),
+        [first, ..] if first & 0x80 != 0 => return Err(DerError::NegativeInteger),
+        [0x00, second, ..] if second & 0x80 == 0 => return Err(DerError::NonMinimalInteger),
+        _ => (),
+    }
+
+    let integer = match integer {
+        [0x00, rest @ ..] => rest,
+        _ => integer,
+    };
+
+    if integer.len() > 32 {
+        return Err(DerError::IntegerTooLarge);
+    }
+
+    let mut padded = vec![0x00; 32 - integer.len()];
+    padded.extend_from_slice(integer);
+
+    Ok((padded, &bytes[2 + length..]))
+}
+
+/// Encodes a big endian unsigned integer as a minimal, positive INTEGER.
+fn encode_integer(integer: &[u8]) -> Vec<u8> {
+    let first = integer.iter().position(|byte| *byte != 0x00).unwrap_or(integer.len() - 1);
+    let mut integer = integer[first..].to_vec();
+
+    if integer[0] & 0x80 != 0 {
+        integer.insert(0, 0x00);
+    }
+
+    [vec![INTEGER_TAG, integer.len() as u8], integer].concat()
+}
+
+/// Returns the name of a sighash type, e.g. `SIGHASH_ALL|ANYONECANPAY`.
+pub fn sighash_type_name(sighash_type: u8) -> Option<String> {
+    let name = match sighash_type & 0x7f {
+        0x01 => "SIGHASH_ALL",
+        0x02 => "SIGHASH_NONE",
+        0x03 => "SIGHASH_SINGLE",
+        _ => return None,
+    };
+
+    match sighash_type & 0x80 != 0 {
+        true => Some(format!("{}|ANYONECANPAY", name)),
+        false => Some(name.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod der_tests {
+    use super::*;
+
+    const R: &str = "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8";
+    const S: &str = "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5";
+    const DER: &str = "3045022100934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d802202442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5";
+
+    fn decode(der: &str) -> Result<DerSignature, DerError> {
+        DerSignature::decode(&hex::decode(der).unwrap())
+    }
+
+    #[test]
+    fn should_decode_and_encode_signatures() {
+        let signature = decode(DER).unwrap();
+
+        assert_eq!(hex::encode(&signature.r), R);
+        assert_eq!(hex::encode(&signature.s), S);
+        assert_eq!(signature.sighash_type, None);
+        assert!(signature.is_low_s());
+        assert_eq!(hex::encode(signature.encode()), DER);
+
+        let with_sighash = decode(&format!("{}81", DER)).unwrap();
+
+        assert_eq!(with_sighash.sighash_type, Some(0x81));
+        assert_eq!(hex::encode(with_sighash.encode()), format!("{}81", DER));
+    }
+
+    #[test]
+    fn should_encode_short_integers_minimally() {
+        let mut r = vec![0x00; 32];
+        r[31] = 0x01;
+
+        let signature = DerSignature { r, s: vec![0xff; 32], sighash_type: Some(0x01) };
+        let der = signature.encode();
+
+        assert_eq!(hex::encode(&der[..8]), "3026020101022100");
+        assert_eq!(DerSignature::decode(&der), Ok(signature.clone()));
+        assert!(!signature.is_low_s());
+    }
+
+    #[test]
+    fn should_throw_error_if_signature_is_not_strict_der() {
+        let high_bit_r = DER.replacen("022100", "0220", 1).replacen("304502", "304402", 1).replacen("00934b", "934b", 1);
+
+        assert_eq!(decode("300602010102"), Err(DerError::InvalidLength(6)));
+        assert_eq!(decode(&DER.replacen("30", "31", 1)), Err(DerError::InvalidSequenceTag(0x31)));
+        assert_eq!(decode(&DER.replacen("3045", "3046", 1)), Err(DerError::LengthMismatch));
+        assert_eq!(decode(&format!("{}0101", DER)), Err(DerError::LengthMismatch));
+        assert_eq!(decode(&DER.replacen("022100", "032100", 1)), Err(DerError::InvalidIntegerTag(0x03)));
+        assert_eq!(decode(&high_bit_r), Err(DerError::NegativeInteger));
+        assert_eq!(decode("3006020002010101"), Err(DerError::ZeroLengthInteger));
+        assert_eq!(decode("300702020001020101"), Err(DerError::NonMinimalInteger));
+    }
+
+    #[test]
+    fn should_name_sighash_types() {
+        assert_eq!(sighash_type_name(0x01), Some("SIGHASH_ALL".to_string()));
+        assert_eq!(sighash_type_name(0x83), Some("SIGHASH_SINGLE|ANYONECANPAY".to_string()));
+        assert_eq!(sighash_type_name(0x04), None);
+    }
+}
@@ -0,0 +1,146 @@
+use secp256k1::{ecdsa, Message, Secp256k1, SecretKey};
+
+use crate::key::PrivateKey;
+use crate::signature::der::{DerError, DerSignature};
+
+#[derive(Debug, PartialEq)]
+pub enum EcdsaError {
+    /// The private key is zero or not below the curve order.
+    InvalidPrivateKey(secp256k1::Error),
+    InvalidDigestLength(usize),
+    InvalidPublicKey(secp256k1::Error),
+    InvalidDer(DerError),
+    /// S is above half the curve order, which standardness rules reject since BIP62.
+    HighS,
+    InvalidSignature(secp256k1::Error),
+}
+
+impl From<DerError> for EcdsaError {
+    fn from(err: DerError) -> Self {
+        EcdsaError::InvalidDer(err)
+    }
+}
+
+/// Signs a 32-byte digest with a nonce derived following RFC6979, so that signing the same
+/// digest with the same key always returns the same signature.
+///
+/// # Return
+///
+/// * The low-S signature, without sighash byte.
+pub fn sign(private_key: &PrivateKey, digest: &[u8]) -> Result<DerSignature, EcdsaError> {
+    let secret_key = SecretKey::from_slice(&private_key.key).map_err(EcdsaError::InvalidPrivateKey)?;
+    let message = Message::from_slice(digest).map_err(|_| EcdsaError::InvalidDigestLength(digest.len()))?;
+
+    let mut signature = Secp256k1::signing_only().sign_ecdsa(&message, &secret_key);
+    signature.normalize_s();
+
+    let compact = signature.serialize_compact();
+
+    Ok(DerSignature { r: compact[..32].to_vec(), s: compact[32..].to_vec(), sighash_type: None })
+}
+
+/// Verifies a strict DER signature of a 32-byte digest, with or without a trailing sighash
+/// byte, rejecting high-S signatures.
+///
+/// # Arguments
+///
+/// * `public_key` - A 33-byte compressed or 65-byte uncompressed public key.
+/// * `digest` - The 32-byte digest signed.
+/// * `signature` - The DER encoded signature.
+pub fn verify(public_key: &[u8], digest: &[u8], signature: &[u8]) -> Result<(), EcdsaError> {
+    let public_key = secp256k1::PublicKey::from_slice(public_key).map_err(EcdsaError::InvalidPublicKey)?;
+    let message = Message::from_slice(digest).map_err(|_| EcdsaError::InvalidDigestLength(digest.len()))?;
+    let signature = DerSignature::decode(signature)?;
+
+    if !signature.is_low_s() {
+        return Err(EcdsaError::HighS);
+    }
+
+    let signature = ecdsa::Signature::from_compact(&signature.compact()).map_err(EcdsaError::InvalidSignature)?;
+
+    Secp256k1::verification_only()
+        .verify_ecdsa(&message, &signature, &public_key)
+        .map_err(EcdsaError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod ecdsa_tests {
+    use super::*;
+    use crate::key::{Key, PublicKey, N};
+
+    fn private_key(key: &str) -> PrivateKey {
+        PrivateKey::from_str(key).unwrap()
+    }
+
+    #[test]
+    fn should_sign_with_rfc6979_nonces() {
+        let n_minus_one = format!("{}0", &N[..63]);
+        let vectors = [
+            (
+                "01",
+                "Satoshi Nakamoto",
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8",
+                "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            ),
+            (
+                "01",
+                "All those moments will be lost in time, like tears in rain. Time to die...",
+                "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b",
+                "547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc21",
+            ),
+            (
+                n_minus_one.as_str(),
+                "Satoshi Nakamoto",
+                "fd567d121db66e382991534ada77a6bd3106f0a1098c231e47993447cd6af2d0",
+                "6b39cd0eb1bc8603e159ef5c20a5c8ad685a45b06ce9bebed3f153d10d93bed5",
+            ),
+            (
+                "f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181",
+                "Alan Turing",
+                "7063ae83e7f62bbb171798131b4a0564b956930092b33b07b395615d9ec7e15c",
+                "58dfcc1e00a35e1572f366ffe34ba0fc47db1e7189759b9fb233c5b05ab388ea",
+            ),
+        ];
+
+        for (key, message, r, s) in vectors {
+            let private_key = private_key(key);
+            let digest = message.as_bytes().to_vec().sha256();
+            let signature = sign(&private_key, &digest).unwrap();
+
+            assert_eq!(hex::encode(&signature.r), r);
+            assert_eq!(hex::encode(&signature.s), s);
+
+            let public_key = PublicKey::from_private_key(private_key);
+
+            assert_eq!(verify(&public_key.compressed, &digest, &signature.encode()), Ok(()));
+            assert_eq!(verify(&public_key.uncompressed, &digest, &signature.encode()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn should_reject_high_s_and_wrong_digest() {
+        let private_key = private_key("01");
+        let public_key = PublicKey::from_private_key(private_key.clone()).compressed;
+        let digest = b"Satoshi Nakamoto".to_vec().sha256();
+        let mut signature = sign(&private_key, &digest).unwrap();
+
+        assert_eq!(
+            verify(&public_key, &[0x00; 32], &signature.encode()),
+            Err(EcdsaError::InvalidSignature(secp256k1::Error::IncorrectSignature))
+        );
+
+        // N - S, the high-S twin of the signature.
+        signature.s = hex::decode("dbbd3162d46e9f9bef7feb87c16dc13b4f6568a87f4e83f728e2443ba586675c").unwrap();
+
+        assert_eq!(verify(&public_key, &digest, &signature.encode()), Err(EcdsaError::HighS));
+        assert_eq!(sign(&private_key, &[0x00; 31]), Err(EcdsaError::InvalidDigestLength(31)));
+    }
+
+    #[test]
+    fn should_throw_error_if_private_key_is_invalid() {
+        assert_eq!(
+            sign(&private_key("0"), &[0x00; 32]),
+            Err(EcdsaError::InvalidPrivateKey(secp256k1::Error::InvalidSecretKey))
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use secp256k1::{schnorr, KeyPair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
+
+use crate::key::{PrivateKey, PublicKey};
+
+#[derive(Debug, PartialEq)]
+pub enum SchnorrError {
+    /// Only 32-byte messages are signed, usually digests or tagged hashes.
+    InvalidMessageLength(usize),
+    InvalidAuxRandLength(usize),
+    /// The private key is zero or not below the curve order.
+    InvalidPrivateKey(secp256k1::Error),
+    InvalidPublicKey(secp256k1::Error),
+    InvalidSignature(secp256k1::Error),
+}
+
+/// Returns the 32-byte x-only public key of a private key, as used by BIP340.
+pub fn x_only_public_key(private_key: &PrivateKey) -> Vec<u8> {
+    PublicKey::from_private_key(private_key.clone()).compressed[1..].to_vec()
+}
+
+/// Signs a message with BIP340.
+///
+/// # Arguments
+///
+/// * `private_key` - The key to sign with, whose x-only public key verifies the signature.
+/// * `message` - The 32-byte message, e.g. a tagged hash.
+/// * `aux_rand` - 32 bytes of auxiliary randomness mixed into the nonce, freshly generated if
+///   `None` as BIP340 recommends. All zeros makes signatures deterministic.
+///
+/// # Return
+///
+/// * The 64-byte signature.
+pub fn sign(private_key: &PrivateKey, message: &[u8], aux_rand: Option<&[u8]>) -> Result<Vec<u8>, SchnorrError> {
+    let secp = Secp256k1::signing_only();
+    let secret_key = SecretKey::from_slice(&private_key.key).map_err(SchnorrError::InvalidPrivateKey)?;
+    let key_pair = KeyPair::from_secret_key(&secp, secret_key);
+
+    let message = Message::from_slice(message).map_err(|_| SchnorrError::InvalidMessageLength(message.len()))?;
+
+    let signature = match aux_rand {
+        Some(aux_rand) => {
+            let aux_rand: &[u8; 32] = aux_rand
+                .try_into()
+                .map_err(|_| SchnorrError::InvalidAuxRandLength(aux_rand.len()))?;
+
+            secp.sign_schnorr_with_aux_rand(&message, &key_pair, aux_rand)
+        }
+        None => secp.sign_schnorr(&message, &key_pair),
+    };
+
+    Ok(signature.as_ref().to_vec())
+}
+
+/// Verifies a BIP340 signature of a 32-byte message against an x-only public key.
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<(), SchnorrError> {
+    let public_key = XOnlyPublicKey::from_slice(public_key).map_err(SchnorrError::InvalidPublicKey)?;
+    let message = Message::from_slice(message).map_err(|_| SchnorrError::InvalidMessageLength(message.len()))?;
+    let signature = schnorr::Signature::from_slice(signature).map_err(SchnorrError::InvalidSignature)?;
+
+    Secp256k1::verification_only()
+        .verify_schnorr(&signature, &message, &public_key)
+        .map_err(SchnorrError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod schnorr_tests {
+    use super::*;
+    use crate::key::tagged_hash;
+
+    const PUBLIC_KEY: &str = "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659";
+    const MESSAGE: &str = "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89";
+
+    fn decode(s: &str) -> Vec<u8> {
+        hex::decode(s).unwrap()
+    }
+
+    #[test]
+    fn should_sign_bip340_test_vectors() {
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            ),
+            (
+                "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+                PUBLIC_KEY,
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                MESSAGE,
+                "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            ),
+            (
+                "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+                "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+                "c87aa53824b4d7ae2eb035a2b5bbbccc080e76cdc6d1692c4b0b62d798e6d906",
+                "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+                "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+            ),
+            (
+                "0b432b2677937381aef05bb02a66ecd012773062cf3fa2549e44f58ed2401710",
+                "25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3",
+            ),
+        ];
+
+        for (private_key, public_key, aux_rand, message, signature) in vectors {
+            let private_key = PrivateKey::from_str(private_key).unwrap();
+
+            assert_eq!(hex::encode(x_only_public_key(&private_key)), public_key);
+            assert_eq!(
+                hex::encode(sign(&private_key, &decode(message), Some(&decode(aux_rand))).unwrap()),
+                signature
+            );
+            assert_eq!(verify(&decode(public_key), &decode(message), &decode(signature)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn should_verify_bip340_test_vectors() {
+        assert_eq!(
+            verify(
+                &decode("d69c3509bb99e412e68b0fe8544e72837dfa30746d8be2aa65975f29d22dc7b9"),
+                &decode("4df3c3f68fcc83b27e9d42c90431a72499f17875c81a599b566c9889b9696703"),
+                &decode("00000000000000000000003b78ce563f89a0ed9414f5aa28ad0d96d6795f9c6376afb1548af603b3eb45c9f8207dee1060cb71c04e80f593060b07d28308d7f4"),
+            ),
+            Ok(())
+        );
+
+        let invalid_signatures = [
+            "fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a14602975563cc27944640ac607cd107ae10923d9ef7a73c643e166be5ebeafa34b1ac553e2",
+            "1fa62e331edbc21c394792d2ab1100a7b432b013df3f6ff4f99fcb33e0e1515f28890b3edb6e7189b630448b515ce4f8622a954cfe545735aaea5134fccdb2bd",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769961764b3aa9b2ffcb6ef947b6887a226e8d7c93e00c5ed0c1834ff0d0c2e6da6",
+            "0000000000000000000000000000000000000000000000000000000000000000123dda8328af9c23a94c1feecfd123ba4fb73476f0d594dcb65c6425bd186051",
+            "00000000000000000000000000000000000000000000000000000000000000017615fbaf5ae28864013c099742deadb4dba87f11ac6754f93780d5a1837cf197",
+            "4a298dacae57395a15d0795ddbfd1dcb564da82b0f269bc70a74f8220429ba1d69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        ];
+
+        for signature in invalid_signatures {
+            assert!(matches!(
+                verify(&decode(PUBLIC_KEY), &decode(MESSAGE), &decode(signature)),
+                Err(SchnorrError::InvalidSignature(_))
+            ));
+        }
+
+        let valid_signature = "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e17776969e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b";
+
+        for public_key in [
+            "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+        ] {
+            assert!(matches!(
+                verify(&decode(public_key), &decode(MESSAGE), &decode(valid_signature)),
+                Err(SchnorrError::InvalidPublicKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn should_sign_tagged_hashes_with_fresh_aux_rand() {
+        let private_key = PrivateKey::from_str("b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef").unwrap();
+        let message = tagged_hash("btcli/test", b"Hello, World!");
+
+        let first = sign(&private_key, &message, None).unwrap();
+        let second = sign(&private_key, &message, None).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(verify(&decode(PUBLIC_KEY), &message, &first), Ok(()));
+        assert_eq!(verify(&decode(PUBLIC_KEY), &message, &second), Ok(()));
+    }
+
+    #[test]
+    fn should_throw_error_if_lengths_are_invalid() {
+        let private_key = PrivateKey::from_str("03").unwrap();
+
+        assert_eq!(sign(&private_key, b"short", None), Err(SchnorrError::InvalidMessageLength(5)));
+        assert_eq!(
+            sign(&private_key, &[0x00; 32], Some(&[0x00; 31])),
+            Err(SchnorrError::InvalidAuxRandLength(31))
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_private_key_is_invalid() {
+        let private_key = PrivateKey::from_str("0").unwrap();
+
+        assert_eq!(
+            sign(&private_key, &[0x00; 32], None),
+            Err(SchnorrError::InvalidPrivateKey(secp256k1::Error::InvalidSecretKey))
+        );
+    }
+}
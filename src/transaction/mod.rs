@@ -0,0 +1,399 @@
+pub mod sighash;
+pub mod signer;
+
+#[cfg(test)]
+mod test_vectors;
+
+use std::str::FromStr;
+
+use crate::key::Key;
+use crate::utils::{compact_size, ToByteArray};
+
+/// The factor by which non-witness bytes weigh more than witness bytes, following BIP141.
+pub const WITNESS_SCALE_FACTOR: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum TransactionError {
+    InvalidHex(hex::FromHexError),
+    /// The transaction ends before one of its fields.
+    UnexpectedEnd,
+    /// A length is not encoded on the fewest bytes possible.
+    NonCanonicalCompactSize,
+    /// The flag following the segwit marker is not 0x01.
+    InvalidFlag(u8),
+    /// The transaction has the segwit marker but none of its inputs has a witness.
+    SuperfluousWitness,
+    /// Bytes are left after the locktime.
+    TrailingBytes(usize),
+}
+
+impl From<hex::FromHexError> for TransactionError {
+    fn from(err: hex::FromHexError) -> Self {
+        TransactionError::InvalidHex(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxIn {
+    /// The txid of the previous output, in internal byte order.
+    pub txid: Vec<u8>,
+    pub vout: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+    pub witness: Vec<Vec<u8>>,
+}
+
+impl TxIn {
+    /// Returns whether the input is the one of a coinbase transaction, spending no output.
+    pub fn is_coinbase(&self) -> bool {
+        self.txid.iter().all(|byte| *byte == 0x00) && self.vout == 0xffffffff
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxOut {
+    /// The value in satoshis.
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+impl TxOut {
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = self.value.to_le_bytes().to_vec();
+        bytes.extend(compact_size(self.script_pubkey.len() as u64));
+        bytes.extend_from_slice(&self.script_pubkey);
+        bytes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transaction {
+    pub version: i32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub lock_time: u32,
+}
+
+impl Transaction {
+    /// Returns whether at least one input has a witness, i.e. whether the transaction is
+    /// serialized with the segwit marker and flag.
+    pub fn has_witness(&self) -> bool {
+        self.inputs.iter().any(|input| !input.witness.is_empty())
+    }
+
+    /// Serializes the transaction, in the BIP144 format if it has a witness.
+    pub fn serialize(&self) -> Vec<u8> {
+        self.serialize_with(self.has_witness())
+    }
+
+    /// Serializes the transaction in the legacy format, the one committed to by its txid.
+    pub fn serialize_without_witness(&self) -> Vec<u8> {
+        self.serialize_with(false)
+    }
+
+    fn serialize_with(&self, with_witness: bool) -> Vec<u8> {
+        let mut bytes = self.version.to_le_bytes().to_vec();
+
+        if with_witness {
+            bytes.extend_from_slice(&[0x00, 0x01]);
+        }
+
+        bytes.extend(compact_size(self.inputs.len() as u64));
+
+        for input in self.inputs.iter() {
+            bytes.extend_from_slice(&input.txid);
+            bytes.extend_from_slice(&input.vout.to_le_bytes());
+            bytes.extend(compact_size(input.script_sig.len() as u64));
+            bytes.extend_from_slice(&input.script_sig);
+            bytes.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        bytes.extend(compact_size(self.outputs.len() as u64));
+
+        for output in self.outputs.iter() {
+            bytes.extend(output.serialize());
+        }
+
+        if with_witness {
+            for input in self.inputs.iter() {
+                bytes.extend(serialize_witness(&input.witness));
+            }
+        }
+
+        bytes.extend_from_slice(&self.lock_time.to_le_bytes());
+        bytes
+    }
+
+    /// Deserializes a transaction in the legacy or BIP144 format.
+    ///
+    /// As in Bitcoin Core, a transaction without inputs is read as having the segwit marker unless
+    /// the next byte is zero, in which case that byte is its output count and it has no outputs.
+    /// The witnesses of a BIP144 transaction cannot all be empty.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, TransactionError> {
+        let mut reader = Reader::new(bytes);
+
+        let version = i32::from_le_bytes(reader.read_array()?);
+        let mut inputs = reader.read_inputs()?;
+        let mut segwit = false;
+        let mut outputs = None;
+
+        if inputs.is_empty() {
+            match reader.read(1)?[0] {
+                0x00 => outputs = Some(Vec::new()),
+                0x01 => {
+                    segwit = true;
+                    inputs = reader.read_inputs()?;
+                }
+                flag => return Err(TransactionError::InvalidFlag(flag)),
+            }
+        }
+
+        let outputs = match outputs {
+            Some(outputs) => outputs,
+            None => reader.read_outputs()?,
+        };
+
+        if segwit {
+            for input in inputs.iter_mut() {
+                input.witness = reader.read_witness()?;
+            }
+
+            if !inputs.iter().any(|input| !input.witness.is_empty()) {
+                return Err(TransactionError::SuperfluousWitness);
+            }
+        }
+
+        let lock_time = u32::from_le_bytes(reader.read_array()?);
+        reader.finish()?;
+
+        Ok(Transaction { version, inputs, outputs, lock_time })
+    }
+
+    /// Returns the txid in internal byte order, i.e. reversed from how it is displayed.
+    pub fn txid(&self) -> Vec<u8> {
+        self.serialize_without_witness().sha256().sha256()
+    }
+
+    /// Returns the wtxid in internal byte order, equal to the txid without witness.
+    pub fn wtxid(&self) -> Vec<u8> {
+        self.serialize().sha256().sha256()
+    }
+
+    /// Returns the size in bytes of the serialized transaction, witness included.
+    pub fn size(&self) -> usize {
+        self.serialize().len()
+    }
+
+    /// Returns the weight of the transaction: its size without witness times 3, plus its size.
+    pub fn weight(&self) -> usize {
+        self.serialize_without_witness().len() * (WITNESS_SCALE_FACTOR - 1) + self.size()
+    }
+
+    /// Returns the virtual size of the transaction, its weight divided by 4 and rounded up.
+    pub fn vsize(&self) -> usize {
+        self.weight().div_ceil(WITNESS_SCALE_FACTOR)
+    }
+}
+
+impl FromStr for Transaction {
+    type Err = TransactionError;
+
+    /// Parses a raw transaction given as a hex string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Transaction::deserialize(&s.trim().to_string().to_byte_array()?)
+    }
+}
+
+/// Serializes a witness stack: its number of items followed by each item, length prefixed.
+pub fn serialize_witness(witness: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = compact_size(witness.len() as u64);
+
+    for item in witness {
+        bytes.extend(compact_size(item.len() as u64));
+        bytes.extend_from_slice(item);
+    }
+
+    bytes
+}
+
+/// Deserializes a witness stack, which must span all the bytes given.
+pub fn deserialize_witness(bytes: &[u8]) -> Result<Vec<Vec<u8>>, TransactionError> {
+    let mut reader = Reader::new(bytes);
+    let witness = reader.read_witness()?;
+    reader.finish()?;
+
+    Ok(witness)
+}
+
+/// Returns a txid or wtxid in internal byte order as displayed, i.e. reversed.
+pub fn display_txid(txid: &[u8]) -> String {
+    hex::encode(txid.iter().rev().copied().collect::<Vec<u8>>())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, position: 0 }
+    }
+
+    fn read(&mut self, n: usize) -> Result<&'a [u8], TransactionError> {
+        let end = self.position.checked_add(n).ok_or(TransactionError::UnexpectedEnd)?;
+        let bytes = self.bytes.get(self.position..end).ok_or(TransactionError::UnexpectedEnd)?;
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], TransactionError> {
+        Ok(self.read(N)?.try_into().expect("N bytes were read"))
+    }
+
+    fn read_compact_size(&mut self) -> Result<u64, TransactionError> {
+        let (n, minimum) = match self.read(1)?[0] {
+            0xfd => (u16::from_le_bytes(self.read_array()?) as u64, 0xfd),
+            0xfe => (u32::from_le_bytes(self.read_array()?) as u64, 0x10000),
+            0xff => (u64::from_le_bytes(self.read_array()?), 0x100000000),
+            n => return Ok(n as u64),
+        };
+
+        match n >= minimum {
+            true => Ok(n),
+            false => Err(TransactionError::NonCanonicalCompactSize),
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, TransactionError> {
+        let length = usize::try_from(self.read_compact_size()?).map_err(|_| TransactionError::UnexpectedEnd)?;
+
+        Ok(self.read(length)?.to_vec())
+    }
+
+    fn read_inputs(&mut self) -> Result<Vec<TxIn>, TransactionError> {
+        (0..self.read_compact_size()?)
+            .map(|_| {
+                Ok(TxIn {
+                    txid: self.read(32)?.to_vec(),
+                    vout: u32::from_le_bytes(self.read_array()?),
+                    script_sig: self.read_bytes()?,
+                    sequence: u32::from_le_bytes(self.read_array()?),
+                    witness: Vec::new(),
+                })
+            })
+            .collect()
+    }
+
+    fn read_outputs(&mut self) -> Result<Vec<TxOut>, TransactionError> {
+        (0..self.read_compact_size()?)
+            .map(|_| {
+                Ok(TxOut {
+                    value: u64::from_le_bytes(self.read_array()?),
+                    script_pubkey: self.read_bytes()?,
+                })
+            })
+            .collect()
+    }
+
+    fn read_witness(&mut self) -> Result<Vec<Vec<u8>>, TransactionError> {
+        (0..self.read_compact_size()?).map(|_| self.read_bytes()).collect()
+    }
+
+    fn finish(&self) -> Result<(), TransactionError> {
+        match self.bytes.len() - self.position {
+            0 => Ok(()),
+            n => Err(TransactionError::TrailingBytes(n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod transaction_tests {
+    use super::*;
+    use crate::transaction::test_vectors::BIP143_P2WPKH_TX;
+
+    const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+
+    #[test]
+    fn should_decode_legacy_transaction() {
+        let tx = Transaction::from_str(GENESIS_COINBASE).unwrap();
+
+        assert_eq!(tx.version, 1);
+        assert_eq!(tx.inputs.len(), 1);
+        assert!(tx.inputs[0].is_coinbase());
+        assert_eq!(tx.inputs[0].sequence, 0xffffffff);
+        assert_eq!(tx.outputs[0].value, 5_000_000_000);
+        assert_eq!(tx.lock_time, 0);
+        assert!(!tx.has_witness());
+        assert_eq!(display_txid(&tx.txid()), "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b");
+        assert_eq!(tx.wtxid(), tx.txid());
+        assert_eq!((tx.size(), tx.vsize(), tx.weight()), (204, 204, 816));
+        assert_eq!(hex::encode(tx.serialize()), GENESIS_COINBASE);
+    }
+
+    #[test]
+    fn should_decode_segwit_transaction() {
+        let tx = Transaction::from_str(BIP143_P2WPKH_TX).unwrap();
+
+        assert_eq!(tx.inputs.len(), 2);
+        assert!(tx.inputs[0].witness.is_empty());
+        assert_eq!(tx.inputs[1].witness.len(), 2);
+        assert_eq!(tx.inputs[1].vout, 1);
+        assert_eq!(tx.outputs[1].value, 223_450_000);
+        assert_eq!(tx.lock_time, 17);
+        assert_eq!(display_txid(&tx.txid()), "e8151a2af31c368a35053ddd4bdb285a8595c769a3ad83e0fa02314a602d4609");
+        assert_eq!(display_txid(&tx.wtxid()), "c36c38370907df2324d9ce9d149d191192f338b37665a82e78e76a12c909b762");
+        assert_eq!((tx.size(), tx.vsize(), tx.weight()), (343, 261, 1042));
+        assert_eq!(hex::encode(tx.serialize()), BIP143_P2WPKH_TX);
+    }
+
+    #[test]
+    fn should_decode_empty_transaction() {
+        let tx = Transaction::from_str("01000000000000000000").unwrap();
+
+        assert_eq!(tx.version, 1);
+        assert!(tx.inputs.is_empty());
+        assert!(tx.outputs.is_empty());
+        assert_eq!(tx.lock_time, 0);
+        assert_eq!(hex::encode(tx.serialize()), "01000000000000000000");
+    }
+
+    #[test]
+    fn should_round_trip_witness_stacks() {
+        let witness = vec![vec![0x01, 0x02], Vec::new(), vec![0xff; 300]];
+        let bytes = serialize_witness(&witness);
+
+        assert_eq!(&bytes[..6], &[0x03, 0x02, 0x01, 0x02, 0x00, 0xfd]);
+        assert_eq!(deserialize_witness(&bytes), Ok(witness));
+    }
+
+    #[test]
+    fn should_throw_error_if_transaction_is_invalid() {
+        let length = GENESIS_COINBASE.len();
+        let superfluous_witness = format!(
+            "{}0001{}00{}",
+            &GENESIS_COINBASE[..8],
+            &GENESIS_COINBASE[8..length - 8],
+            &GENESIS_COINBASE[length - 8..]
+        );
+
+        assert_eq!(Transaction::from_str("0100"), Err(TransactionError::UnexpectedEnd));
+        assert_eq!(Transaction::from_str(&GENESIS_COINBASE[..400]), Err(TransactionError::UnexpectedEnd));
+        assert_eq!(
+            Transaction::from_str(&format!("{}00", GENESIS_COINBASE)),
+            Err(TransactionError::TrailingBytes(1))
+        );
+        assert_eq!(
+            Transaction::from_str(&BIP143_P2WPKH_TX.replacen("01000000000102", "01000000000202", 1)),
+            Err(TransactionError::InvalidFlag(0x02))
+        );
+        assert_eq!(Transaction::from_str(&superfluous_witness), Err(TransactionError::SuperfluousWitness));
+        assert_eq!(
+            Transaction::from_str(&GENESIS_COINBASE.replacen("0100000001", "01000000fd0100", 1)),
+            Err(TransactionError::NonCanonicalCompactSize)
+        );
+        assert!(matches!(Transaction::from_str("zz"), Err(TransactionError::InvalidHex(_))));
+    }
+}
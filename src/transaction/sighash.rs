@@ -0,0 +1,398 @@
+use crate::key::{tagged_hash, Key};
+use crate::transaction::{Transaction, TxIn, TxOut};
+use crate::utils::compact_size;
+
+/// Signs like SIGHASH_ALL without a sighash byte, only valid for taproot inputs.
+pub const SIGHASH_DEFAULT: u8 = 0x00;
+pub const SIGHASH_ALL: u8 = 0x01;
+pub const SIGHASH_NONE: u8 = 0x02;
+pub const SIGHASH_SINGLE: u8 = 0x03;
+pub const SIGHASH_ANYONECANPAY: u8 = 0x80;
+
+/// The first byte of taproot annexes, which are the last witness item when there are two
+/// or more.
+pub const ANNEX_TAG: u8 = 0x50;
+
+#[derive(Debug, PartialEq)]
+pub enum SighashError {
+    /// Signatures only commit to the six legacy types, or to SIGHASH_DEFAULT for taproot ones.
+    InvalidSighashType(u8),
+    /// The outputs spent by every input are needed to sign taproot inputs.
+    PrevoutsMismatch {
+        inputs: usize,
+        prevouts: usize,
+    },
+    /// Taproot inputs cannot be signed with SIGHASH_SINGLE without an output at their index.
+    MissingSingleOutput(usize),
+    InvalidAnnex,
+}
+
+/// Returns the hash signed by an input with the original, pre-segwit, algorithm.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction spending the input.
+/// * `index` - The index of the input, which must exist.
+/// * `script_code` - The script the input commits to, usually the scriptPubKey of the output
+///   spent, or the redeem script for P2SH. Taken as is, without removing OP_CODESEPARATORs.
+/// * `sighash_type` - The sighash byte appended to the signature.
+///
+/// # Return
+///
+/// * The hash in internal byte order. As in Bitcoin Core, SIGHASH_SINGLE without an output
+///   at the index of the input returns the value 1.
+pub fn legacy_sighash(tx: &Transaction, index: usize, script_code: &[u8], sighash_type: u8) -> Vec<u8> {
+    let base_type = sighash_type & 0x1f;
+    let anyone_can_pay = sighash_type & SIGHASH_ANYONECANPAY != 0;
+
+    if base_type == SIGHASH_SINGLE && index >= tx.outputs.len() {
+        let mut one = vec![0x00; 32];
+        one[0] = 0x01;
+        return one;
+    }
+
+    let inputs = tx
+        .inputs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !anyone_can_pay || *i == index)
+        .map(|(i, input)| {
+            let sequence = match base_type {
+                SIGHASH_NONE | SIGHASH_SINGLE if i != index => 0,
+                _ => input.sequence,
+            };
+            let script_sig = if i == index { script_code.to_vec() } else { Vec::new() };
+
+            TxIn { script_sig, sequence, witness: Vec::new(), ..input.clone() }
+        })
+        .collect();
+
+    let outputs = match base_type {
+        SIGHASH_NONE => Vec::new(),
+        SIGHASH_SINGLE => {
+            let blank = TxOut { value: u64::MAX, script_pubkey: Vec::new() };
+            let mut outputs = vec![blank; index];
+            outputs.push(tx.outputs[index].clone());
+            outputs
+        }
+        _ => tx.outputs.clone(),
+    };
+
+    let tx = Transaction { inputs, outputs, ..tx.clone() };
+
+    let mut bytes = tx.serialize_without_witness();
+    bytes.extend_from_slice(&(sighash_type as u32).to_le_bytes());
+
+    bytes.sha256().sha256()
+}
+
+/// Returns the hash signed by a segwit v0 input, following BIP143.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction spending the input.
+/// * `index` - The index of the input, which must exist.
+/// * `script_code` - The script the input commits to: the P2PKH script of the key hash for
+///   P2WPKH, or the witness script for P2WSH.
+/// * `value` - The value in satoshis of the output spent.
+/// * `sighash_type` - The sighash byte appended to the signature.
+pub fn segwit_v0_sighash(
+    tx: &Transaction,
+    index: usize,
+    script_code: &[u8],
+    value: u64,
+    sighash_type: u8,
+) -> Vec<u8> {
+    let base_type = sighash_type & 0x1f;
+    let anyone_can_pay = sighash_type & SIGHASH_ANYONECANPAY != 0;
+    let input = &tx.inputs[index];
+
+    let hash_prevouts = match anyone_can_pay {
+        true => vec![0x00; 32],
+        false => tx.inputs.iter().flat_map(outpoint).collect::<Vec<u8>>().sha256().sha256(),
+    };
+
+    let hash_sequence = match anyone_can_pay || base_type == SIGHASH_NONE || base_type == SIGHASH_SINGLE {
+        true => vec![0x00; 32],
+        false => {
+            let sequences: Vec<u8> = tx.inputs.iter().flat_map(|input| input.sequence.to_le_bytes()).collect();
+            sequences.sha256().sha256()
+        }
+    };
+
+    let hash_outputs = match base_type {
+        SIGHASH_NONE => vec![0x00; 32],
+        SIGHASH_SINGLE => match tx.outputs.get(index) {
+            Some(output) => output.serialize().sha256().sha256(),
+            None => vec![0x00; 32],
+        },
+        _ => tx.outputs.iter().flat_map(TxOut::serialize).collect::<Vec<u8>>().sha256().sha256(),
+    };
+
+    let mut bytes = tx.version.to_le_bytes().to_vec();
+    bytes.extend(hash_prevouts);
+    bytes.extend(hash_sequence);
+    bytes.extend(outpoint(input));
+    bytes.extend(compact_size(script_code.len() as u64));
+    bytes.extend_from_slice(script_code);
+    bytes.extend_from_slice(&value.to_le_bytes());
+    bytes.extend_from_slice(&input.sequence.to_le_bytes());
+    bytes.extend(hash_outputs);
+    bytes.extend_from_slice(&tx.lock_time.to_le_bytes());
+    bytes.extend_from_slice(&(sighash_type as u32).to_le_bytes());
+
+    bytes.sha256().sha256()
+}
+
+/// Returns the hash signed by a taproot input, following BIP341.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction spending the input.
+/// * `index` - The index of the input, which must exist.
+/// * `prevouts` - The outputs spent by every input of the transaction, in order.
+/// * `sighash_type` - The sighash byte of the signature, or SIGHASH_DEFAULT if it has none.
+/// * `annex` - The annex of the witness, starting with 0x50, if any.
+/// * `leaf_hash` - The TapLeaf hash of the script of script-path spends, `None` for key-path
+///   spends. Signatures are assumed to follow the last OP_CODESEPARATOR of the script.
+pub fn taproot_sighash(
+    tx: &Transaction,
+    index: usize,
+    prevouts: &[TxOut],
+    sighash_type: u8,
+    annex: Option<&[u8]>,
+    leaf_hash: Option<&[u8]>,
+) -> Result<Vec<u8>, SighashError> {
+    if !matches!(sighash_type, 0x00..=0x03 | 0x81..=0x83) {
+        return Err(SighashError::InvalidSighashType(sighash_type));
+    }
+
+    if prevouts.len() != tx.inputs.len() {
+        return Err(SighashError::PrevoutsMismatch { inputs: tx.inputs.len(), prevouts: prevouts.len() });
+    }
+
+    if annex.is_some_and(|annex| annex.first() != Some(&ANNEX_TAG)) {
+        return Err(SighashError::InvalidAnnex);
+    }
+
+    let base_type = sighash_type & 0x03;
+    let anyone_can_pay = sighash_type & SIGHASH_ANYONECANPAY != 0;
+    let input = &tx.inputs[index];
+
+    // The epoch, always 0, followed by the message of the signature.
+    let mut bytes = vec![0x00, sighash_type];
+    bytes.extend_from_slice(&tx.version.to_le_bytes());
+    bytes.extend_from_slice(&tx.lock_time.to_le_bytes());
+
+    if !anyone_can_pay {
+        let amounts: Vec<u8> = prevouts.iter().flat_map(|prevout| prevout.value.to_le_bytes()).collect();
+        let script_pubkeys: Vec<u8> = prevouts
+            .iter()
+            .flat_map(|prevout| {
+                [compact_size(prevout.script_pubkey.len() as u64), prevout.script_pubkey.clone()].concat()
+            })
+            .collect();
+        let sequences: Vec<u8> = tx.inputs.iter().flat_map(|input| input.sequence.to_le_bytes()).collect();
+
+        bytes.extend(tx.inputs.iter().flat_map(outpoint).collect::<Vec<u8>>().sha256());
+        bytes.extend(amounts.sha256());
+        bytes.extend(script_pubkeys.sha256());
+        bytes.extend(sequences.sha256());
+    }
+
+    if base_type != SIGHASH_NONE && base_type != SIGHASH_SINGLE {
+        bytes.extend(tx.outputs.iter().flat_map(TxOut::serialize).collect::<Vec<u8>>().sha256());
+    }
+
+    let spend_type = (leaf_hash.is_some() as u8) * 2 + annex.is_some() as u8;
+    bytes.push(spend_type);
+
+    match anyone_can_pay {
+        true => {
+            bytes.extend(outpoint(input));
+            bytes.extend(prevouts[index].serialize());
+            bytes.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        false => bytes.extend_from_slice(&(index as u32).to_le_bytes()),
+    }
+
+    if let Some(annex) = annex {
+        bytes.extend([compact_size(annex.len() as u64), annex.to_vec()].concat().sha256());
+    }
+
+    if base_type == SIGHASH_SINGLE {
+        let output = tx.outputs.get(index).ok_or(SighashError::MissingSingleOutput(index))?;
+        bytes.extend(output.serialize().sha256());
+    }
+
+    if let Some(leaf_hash) = leaf_hash {
+        bytes.extend_from_slice(leaf_hash);
+        // The key version, 0 for BIP342 keys, and the position of the last OP_CODESEPARATOR.
+        bytes.push(0x00);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    }
+
+    Ok(tagged_hash("TapSighash", &bytes))
+}
+
+/// Serializes the outpoint an input spends: the txid followed by the output index.
+fn outpoint(input: &TxIn) -> Vec<u8> {
+    [&input.txid[..], &input.vout.to_le_bytes()].concat()
+}
+
+#[cfg(test)]
+mod sighash_tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::signature::ecdsa;
+    use crate::transaction::test_vectors::*;
+
+    /// The TapLeaf hash of the only script of the output spent by the second input.
+    const BIP341_LEAF_HASH: &str = "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21";
+
+    #[test]
+    fn should_compute_legacy_sighash_all() {
+        let tx = Transaction::from_str(BIP143_P2WPKH_TX).unwrap();
+        let script_code = hex::decode(format!("21{}ac", P2PK_PUBLIC_KEY)).unwrap();
+        let signature = &tx.inputs[0].script_sig[1..];
+
+        let sighash = legacy_sighash(&tx, 0, &script_code, SIGHASH_ALL);
+
+        assert_eq!(hex::encode(&sighash), "63cec688ee06a91e913875356dd4dea2f8e0f2a2659885372da2a37e32c7532e");
+        assert_eq!(ecdsa::verify(&hex::decode(P2PK_PUBLIC_KEY).unwrap(), &sighash, signature), Ok(()));
+    }
+
+    #[test]
+    fn should_compute_legacy_sighash_of_every_type() {
+        let tx = Transaction::from_str(BIP143_P2WPKH_TX).unwrap();
+        let script_code = hex::decode("76a9141d0f172a0ecb48aee1be1f2687d2963ae33f71a188ac").unwrap();
+
+        let vectors = [
+            (SIGHASH_ALL, "c46030820cbc48402a47cc5b5d3d41648f4e3a711f56b804d601d09dc112a6a4"),
+            (SIGHASH_NONE, "ffbbcf554debe55f76a79db7d205edc891f194184a93a660366bb8f7facb89e2"),
+            (SIGHASH_SINGLE, "33cd468bd6b82f04bcef180b748c521d6fdee3b11711a2f27b2e465915afaec2"),
+            (SIGHASH_ALL | SIGHASH_ANYONECANPAY, "8cfeea8cfe3a35332ec31f53900716682d964e0c16372b1f7689ed93f3a40756"),
+            (SIGHASH_NONE | SIGHASH_ANYONECANPAY, "bd8ca4cb1ab60a8db8451bd58bc068a9abd5ea20a08029b38934c9d50c1d6721"),
+            (SIGHASH_SINGLE | SIGHASH_ANYONECANPAY, "865c7791b88917498a4c402176c302f146c53a6c2f50ecda08548f515237dca6"),
+        ];
+
+        for (sighash_type, expected) in vectors {
+            assert_eq!(hex::encode(legacy_sighash(&tx, 1, &script_code, sighash_type)), expected);
+        }
+    }
+
+    #[test]
+    fn should_compute_bip143_sighash_of_p2wpkh_inputs() {
+        let tx = Transaction::from_str(BIP143_P2WPKH_TX).unwrap();
+        let script_code = hex::decode("76a9141d0f172a0ecb48aee1be1f2687d2963ae33f71a188ac").unwrap();
+        let sighash = segwit_v0_sighash(&tx, 1, &script_code, 600_000_000, SIGHASH_ALL);
+
+        assert_eq!(hex::encode(sighash), "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670");
+    }
+
+    #[test]
+    fn should_compute_bip143_sighash_of_every_type() {
+        let tx = Transaction::from_str(BIP143_P2SH_P2WSH_TX).unwrap();
+        let witness_script = hex::decode(BIP143_WITNESS_SCRIPT).unwrap();
+
+        let vectors = [
+            (SIGHASH_ALL, "185c0be5263dce5b4bb50a047973c1b6272bfbd0103a89444597dc40b248ee7c"),
+            (SIGHASH_NONE, "e9733bc60ea13c95c6527066bb975a2ff29a925e80aa14c213f686cbae5d2f36"),
+            (SIGHASH_SINGLE, "1e1f1c303dc025bd664acb72e583e933fae4cff9148bf78c157d1e8f78530aea"),
+            (SIGHASH_ALL | SIGHASH_ANYONECANPAY, "2a67f03e63a6a422125878b40b82da593be8d4efaafe88ee528af6e5a9955c6e"),
+            (SIGHASH_NONE | SIGHASH_ANYONECANPAY, "781ba15f3779d5542ce8ecb5c18716733a5ee42a6f51488ec96154934e2c890a"),
+            (SIGHASH_SINGLE | SIGHASH_ANYONECANPAY, "511e8e52ed574121fc1b654970395502128263f62662e076dc6baf05c2e6a99b"),
+        ];
+
+        for (sighash_type, expected) in vectors {
+            let sighash = segwit_v0_sighash(&tx, 0, &witness_script, 987_654_321, sighash_type);
+
+            assert_eq!(hex::encode(sighash), expected);
+        }
+    }
+
+    #[test]
+    fn should_return_one_for_sighash_single_without_output() {
+        let mut tx = Transaction::from_str(BIP143_P2WPKH_TX).unwrap();
+        tx.outputs.truncate(1);
+
+        let sighash = legacy_sighash(&tx, 1, &[], SIGHASH_SINGLE);
+
+        assert_eq!(hex::encode(sighash), format!("01{}", "00".repeat(31)));
+    }
+
+    #[test]
+    fn should_compute_bip341_sighash_of_key_path_spends() {
+        let tx = Transaction::from_str(BIP341_TX).unwrap();
+        let prevouts = bip341_prevouts();
+
+        let vectors = [
+            (0, SIGHASH_SINGLE, "2514a6272f85cfa0f45eb907fcb0d121b808ed37c6ea160a5a9046ed5526d555"),
+            (
+                1,
+                SIGHASH_SINGLE | SIGHASH_ANYONECANPAY,
+                "325a644af47e8a5a2591cda0ab0723978537318f10e6a63d4eed783b96a71a4d",
+            ),
+            (3, SIGHASH_ALL, "bf013ea93474aa67815b1b6cc441d23b64fa310911d991e713cd34c7f5d46669"),
+            (4, SIGHASH_DEFAULT, "4f900a0bae3f1446fd48490c2958b5a023228f01661cda3496a11da502a7f7ef"),
+            (6, SIGHASH_NONE, "15f25c298eb5cdc7eb1d638dd2d45c97c4c59dcaec6679cfc16ad84f30876b85"),
+            (
+                7,
+                SIGHASH_NONE | SIGHASH_ANYONECANPAY,
+                "cd292de50313804dabe4685e83f923d2969577191a3e1d2882220dca88cbeb10",
+            ),
+            (8, SIGHASH_ALL | SIGHASH_ANYONECANPAY, "cccb739eca6c13a8a89e6e5cd317ffe55669bbda23f2fd37b0f18755e008edd2"),
+        ];
+
+        for (index, sighash_type, expected) in vectors {
+            let sighash = taproot_sighash(&tx, index, &prevouts, sighash_type, None, None).unwrap();
+
+            assert_eq!(hex::encode(sighash), expected);
+        }
+    }
+
+    /// BIP341 only publishes key-path vectors, so these hashes were computed separately with a
+    /// from-scratch Python implementation of the BIP341 signature message, which reproduces
+    /// every keyPathSpending sighash above, on the same transaction.
+    #[test]
+    fn should_compute_bip341_sighash_of_script_path_spends_and_annexes() {
+        let tx = Transaction::from_str(BIP341_TX).unwrap();
+        let prevouts = bip341_prevouts();
+        let leaf_hash = hex::decode(BIP341_LEAF_HASH).unwrap();
+        let annex = [ANNEX_TAG, 0xff];
+
+        let script_path = taproot_sighash(&tx, 1, &prevouts, SIGHASH_DEFAULT, None, Some(&leaf_hash)).unwrap();
+        let with_annex = taproot_sighash(&tx, 1, &prevouts, 0x83, Some(&annex), Some(&leaf_hash)).unwrap();
+        let key_path_with_annex =
+            taproot_sighash(&tx, 0, &prevouts, SIGHASH_DEFAULT, Some(&[ANNEX_TAG]), None).unwrap();
+
+        assert_eq!(hex::encode(script_path), "c6c88b99952a1da24f5efe63b6da76032667e88e0caeb2059bdb64ebfce0fcbe");
+        assert_eq!(hex::encode(with_annex), "069f63632981665defde9ef348aa2ab2d76c85d4b69a187bc8ae956cdeb6525e");
+        assert_eq!(
+            hex::encode(key_path_with_annex),
+            "07dd75978b3d505a3e30d744497cad93070316cd07c0f6f9d9b9f783f5dd744f"
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_taproot_input_cannot_be_hashed() {
+        let tx = Transaction::from_str(BIP341_TX).unwrap();
+        let prevouts = bip341_prevouts();
+
+        assert_eq!(taproot_sighash(&tx, 0, &prevouts, 0x04, None, None), Err(SighashError::InvalidSighashType(0x04)));
+        assert_eq!(taproot_sighash(&tx, 0, &prevouts, 0x80, None, None), Err(SighashError::InvalidSighashType(0x80)));
+        assert_eq!(
+            taproot_sighash(&tx, 0, &prevouts[1..], SIGHASH_DEFAULT, None, None),
+            Err(SighashError::PrevoutsMismatch { inputs: 9, prevouts: 8 })
+        );
+        assert_eq!(
+            taproot_sighash(&tx, 3, &prevouts, SIGHASH_SINGLE, None, None),
+            Err(SighashError::MissingSingleOutput(3))
+        );
+        assert_eq!(
+            taproot_sighash(&tx, 0, &prevouts, SIGHASH_DEFAULT, Some(&[0x51]), None),
+            Err(SighashError::InvalidAnnex)
+        );
+    }
+}
@@ -0,0 +1,859 @@
+use std::str::FromStr;
+
+use secp256k1::SecretKey;
+
+use crate::address::{Address, AddressType};
+use crate::key::{
+    tap_branch_hash, tap_leaf_hash, taproot_tweak_private_key, taproot_tweak_public_key, Key, PrivateKey, PublicKey,
+};
+use crate::script::{minimal_push_opcode, push_data};
+use crate::signature::ecdsa::EcdsaError;
+use crate::signature::schnorr::SchnorrError;
+use crate::signature::{ecdsa, schnorr};
+use crate::transaction::sighash::{
+    legacy_sighash, segwit_v0_sighash, taproot_sighash, SighashError, SIGHASH_ALL, SIGHASH_DEFAULT,
+};
+use crate::transaction::{Transaction, TxIn, TxOut};
+
+/// The version of the transactions built, the one Bitcoin Core has used since BIP68.
+const VERSION: i32 = 2;
+
+/// The 21 million bitcoins that will ever exist, in satoshis, the most any amount can be.
+pub const MAX_MONEY: u64 = 2_100_000_000_000_000;
+
+/// Control blocks hold the leaf version and internal key, then up to 128 hashes of the path.
+const CONTROL_BLOCK_BASE_SIZE: usize = 33;
+const CONTROL_BLOCK_NODE_SIZE: usize = 32;
+const CONTROL_BLOCK_MAX_NODES: usize = 128;
+
+#[derive(Debug, PartialEq)]
+pub enum SigningError {
+    /// The UTXO is not given as `txid:vout:value:scriptPubKey`.
+    InvalidUtxo(String),
+    NoInputs,
+    NoOutputs,
+    /// The outputs are worth more than the UTXOs spent.
+    InsufficientFunds { inputs: u64, outputs: u64 },
+    /// The UTXOs spent, or the outputs created, add up to more than MAX_MONEY.
+    AmountOutOfRange,
+    /// Signing needs exactly one UTXO for each input of the transaction.
+    UtxoCountMismatch { inputs: usize, utxos: usize },
+    /// The transaction has no input at this index.
+    InvalidInputIndex(usize),
+    /// The output spent by the input at this index is of a type that cannot be signed.
+    UnsupportedScript(usize),
+    /// None of the keys given can sign the input at this index.
+    MissingKey(usize),
+    InvalidSighash(SighashError),
+    EcdsaSigningFailed(EcdsaError),
+    SchnorrSigningFailed(SchnorrError),
+    /// The control block of the input at this index does not commit the leaf script to the
+    /// output key of the output spent.
+    InvalidControlBlock(usize),
+}
+
+impl From<EcdsaError> for SigningError {
+    fn from(err: EcdsaError) -> Self {
+        SigningError::EcdsaSigningFailed(err)
+    }
+}
+
+impl From<SchnorrError> for SigningError {
+    fn from(err: SchnorrError) -> Self {
+        SigningError::SchnorrSigningFailed(err)
+    }
+}
+
+impl From<SighashError> for SigningError {
+    fn from(err: SighashError) -> Self {
+        SigningError::InvalidSighash(err)
+    }
+}
+
+/// How a taproot output is spent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaprootSpend {
+    /// Signs with the output key: the internal key tweaked with the root of the script tree.
+    KeyPath {
+        /// The 32-byte root of the script tree, `None` for key-path only outputs such as BIP86.
+        merkle_root: Option<Vec<u8>>,
+    },
+    /// Signs for a script of the tree with a key it checks, which is not tweaked.
+    ScriptPath {
+        leaf_script: Vec<u8>,
+        /// The leaf version and parity byte, the internal key and the path of the leaf.
+        control_block: Vec<u8>,
+    },
+}
+
+/// An unspent output to spend, along with what is needed to sign for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utxo {
+    /// The txid of the transaction of the output, in internal byte order.
+    pub txid: Vec<u8>,
+    pub vout: u32,
+    /// The value in satoshis.
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+impl FromStr for Utxo {
+    type Err = SigningError;
+
+    /// Parses a UTXO given as `txid:vout:value:scriptPubKey`, with the txid as displayed, the
+    /// value in satoshis, at most MAX_MONEY, and the scriptPubKey as a hex string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SigningError::InvalidUtxo(s.to_string());
+
+        let (txid, vout, value, script_pubkey) = match s.split(':').collect::<Vec<&str>>()[..] {
+            [txid, vout, value, script_pubkey] => (txid, vout, value, script_pubkey),
+            _ => return Err(invalid()),
+        };
+
+        let mut txid = hex::decode(txid).map_err(|_| invalid())?;
+
+        if txid.len() != 32 {
+            return Err(invalid());
+        }
+
+        txid.reverse();
+
+        let value = value.parse().map_err(|_| invalid())?;
+
+        if value > MAX_MONEY {
+            return Err(invalid());
+        }
+
+        Ok(Utxo {
+            txid,
+            vout: vout.parse().map_err(|_| invalid())?,
+            value,
+            script_pubkey: hex::decode(script_pubkey).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl From<&Utxo> for TxOut {
+    fn from(utxo: &Utxo) -> Self {
+        TxOut { value: utxo.value, script_pubkey: utxo.script_pubkey.clone() }
+    }
+}
+
+/// Builds an unsigned transaction spending UTXOs to addresses, without locktime nor RBF.
+///
+/// # Arguments
+///
+/// * `utxos` - The outputs spent, in the order of the inputs.
+/// * `destinations` - The addresses paid and their amounts in satoshis, in the order of the
+///   outputs.
+///
+/// # Return
+///
+/// * The transaction, whose fee is whatever the UTXOs are worth more than the outputs.
+pub fn build_transaction(utxos: &[Utxo], destinations: &[(Address, u64)]) -> Result<Transaction, SigningError> {
+    if utxos.is_empty() {
+        return Err(SigningError::NoInputs);
+    }
+
+    if destinations.is_empty() {
+        return Err(SigningError::NoOutputs);
+    }
+
+    let inputs = total_amount(utxos.iter().map(|utxo| utxo.value))?;
+    let outputs = total_amount(destinations.iter().map(|(_, value)| *value))?;
+
+    if outputs > inputs {
+        return Err(SigningError::InsufficientFunds { inputs, outputs });
+    }
+
+    Ok(Transaction {
+        version: VERSION,
+        inputs: utxos
+            .iter()
+            .map(|utxo| TxIn {
+                txid: utxo.txid.clone(),
+                vout: utxo.vout,
+                script_sig: Vec::new(),
+                sequence: 0xffffffff,
+                witness: Vec::new(),
+            })
+            .collect(),
+        outputs: destinations
+            .iter()
+            .map(|(address, value)| TxOut { value: *value, script_pubkey: address.script_pubkey() })
+            .collect(),
+        lock_time: 0,
+    })
+}
+
+/// Adds up amounts in satoshis, failing if any partial sum goes above MAX_MONEY as Bitcoin
+/// Core does.
+pub fn total_amount(mut amounts: impl Iterator<Item = u64>) -> Result<u64, SigningError> {
+    amounts.try_fold(0u64, |total, amount| {
+        total.checked_add(amount).filter(|total| *total <= MAX_MONEY).ok_or(SigningError::AmountOutOfRange)
+    })
+}
+
+/// Signs every input of a transaction with SIGHASH_ALL, or SIGHASH_DEFAULT for taproot inputs.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction, whose inputs spend the UTXOs in order.
+/// * `utxos` - The outputs spent.
+/// * `keys` - The private keys, matched against each UTXO through their public key hash, or
+///   their BIP86 output key for taproot.
+pub fn sign_transaction(tx: &mut Transaction, utxos: &[Utxo], keys: &[PrivateKey]) -> Result<(), SigningError> {
+    check_utxos(tx, utxos)?;
+
+    for (index, utxo) in utxos.iter().enumerate() {
+        let sighash_type = match is_taproot(utxo) {
+            true => SIGHASH_DEFAULT,
+            false => SIGHASH_ALL,
+        };
+
+        sign_input(tx, index, utxos, keys, sighash_type)?;
+    }
+
+    Ok(())
+}
+
+/// Signs an input, setting its scriptSig and witness.
+///
+/// P2PKH outputs are signed with the legacy algorithm, with compressed or uncompressed keys.
+/// P2WPKH outputs, and P2SH outputs which are assumed to wrap P2WPKH, are signed following
+/// BIP143 with compressed keys only. P2TR outputs are spent through their key path, assuming
+/// they commit to no script tree as in BIP86.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction spending the input.
+/// * `index` - The index of the input, an InvalidInputIndex error if the transaction has none.
+/// * `utxos` - The outputs spent by every input, all of which taproot signatures commit to.
+/// * `keys` - The private keys, one of which must be the one of the output.
+/// * `sighash_type` - The sighash byte of the signature.
+pub fn sign_input(
+    tx: &mut Transaction,
+    index: usize,
+    utxos: &[Utxo],
+    keys: &[PrivateKey],
+    sighash_type: u8,
+) -> Result<(), SigningError> {
+    check_utxos(tx, utxos)?;
+
+    if index >= tx.inputs.len() {
+        return Err(SigningError::InvalidInputIndex(index));
+    }
+
+    let utxo = &utxos[index];
+    let address = Address::from_script_pubkey(&utxo.script_pubkey, Default::default())
+        .ok_or(SigningError::UnsupportedScript(index))?;
+
+    match address.address_type {
+        AddressType::P2pkh => {
+            let (private_key, public_key) =
+                find_key(keys, true, |public_key| public_key.hash160() == address.payload)?
+                    .ok_or(SigningError::MissingKey(index))?;
+
+            let sighash = legacy_sighash(tx, index, &utxo.script_pubkey, sighash_type);
+            let signature = sign(private_key, &sighash, sighash_type)?;
+
+            tx.inputs[index].script_sig = push_all(&[&signature, &public_key]);
+        }
+        AddressType::P2wpkh | AddressType::P2sh => {
+            let nested = address.address_type == AddressType::P2sh;
+
+            let (private_key, public_key) = find_key(keys, false, |public_key| match nested {
+                true => p2wpkh_script(public_key).hash160() == address.payload,
+                false => public_key.hash160() == address.payload,
+            })?
+            .ok_or(SigningError::MissingKey(index))?;
+
+            let script_code = Address::p2pkh(public_key.clone().hash160(), Default::default()).script_pubkey();
+            let sighash = segwit_v0_sighash(tx, index, &script_code, utxo.value, sighash_type);
+            let signature = sign(private_key, &sighash, sighash_type)?;
+
+            if nested {
+                tx.inputs[index].script_sig = push_all(&[&p2wpkh_script(public_key.clone())]);
+            }
+
+            tx.inputs[index].witness = vec![signature, public_key];
+        }
+        AddressType::P2tr => {
+            let private_key = keys
+                .iter()
+                .find(|private_key| {
+                    let tweaked = taproot_tweak_private_key(private_key, None);
+                    tweaked.map(|key| schnorr::x_only_public_key(&key)).as_ref() == Ok(&address.payload)
+                })
+                .ok_or(SigningError::MissingKey(index))?;
+
+            let spend = TaprootSpend::KeyPath { merkle_root: None };
+            sign_taproot_input(tx, index, utxos, private_key, &spend, sighash_type, None, None)?;
+        }
+        _ => return Err(SigningError::UnsupportedScript(index)),
+    }
+
+    Ok(())
+}
+
+/// Signs a taproot input following BIP341 and BIP342, setting its witness.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction spending the input.
+/// * `index` - The index of the input, an InvalidInputIndex error if the transaction has none.
+/// * `utxos` - The outputs spent by every input, all of which the signature commits to.
+/// * `private_key` - The internal key for key-path spends, which is tweaked before signing,
+///   or a key checked by the leaf script for script-path spends.
+/// * `spend` - Whether to spend through the key path or a script of the tree.
+/// * `sighash_type` - The sighash byte appended to the signature, or SIGHASH_DEFAULT to
+///   append none.
+/// * `annex` - The annex to commit to and append to the witness, starting with 0x50, if any.
+/// * `aux_rand` - 32 bytes of auxiliary randomness for the BIP340 nonce, freshly generated if
+///   `None`. All zeros makes signatures deterministic, as in the BIP341 test vectors.
+///
+/// # Return
+///
+/// * Nothing, the witness is the signature, followed by the leaf script and control block
+///   for script-path spends, then by the annex if any.
+#[allow(clippy::too_many_arguments)]
+pub fn sign_taproot_input(
+    tx: &mut Transaction,
+    index: usize,
+    utxos: &[Utxo],
+    private_key: &PrivateKey,
+    spend: &TaprootSpend,
+    sighash_type: u8,
+    annex: Option<&[u8]>,
+    aux_rand: Option<&[u8]>,
+) -> Result<(), SigningError> {
+    check_utxos(tx, utxos)?;
+
+    if index >= tx.inputs.len() {
+        return Err(SigningError::InvalidInputIndex(index));
+    }
+
+    if !is_taproot(&utxos[index]) {
+        return Err(SigningError::UnsupportedScript(index));
+    }
+
+    let output_key = &utxos[index].script_pubkey[2..];
+    let prevouts: Vec<TxOut> = utxos.iter().map(TxOut::from).collect();
+
+    let (signing_key, leaf_hash, mut witness) = match spend {
+        TaprootSpend::KeyPath { merkle_root } => {
+            let tweaked = taproot_tweak_private_key(private_key, merkle_root.as_deref())
+                .map_err(|_| SigningError::MissingKey(index))?;
+
+            if schnorr::x_only_public_key(&tweaked) != output_key {
+                return Err(SigningError::MissingKey(index));
+            }
+
+            (tweaked, None, Vec::new())
+        }
+        TaprootSpend::ScriptPath { leaf_script, control_block } => {
+            let leaf_hash = verify_control_block(leaf_script, control_block, output_key)
+                .ok_or(SigningError::InvalidControlBlock(index))?;
+
+            (private_key.clone(), Some(leaf_hash), vec![leaf_script.clone(), control_block.clone()])
+        }
+    };
+
+    let sighash = taproot_sighash(tx, index, &prevouts, sighash_type, annex, leaf_hash.as_deref())?;
+    let mut signature = schnorr::sign(&signing_key, &sighash, aux_rand)?;
+
+    if sighash_type != SIGHASH_DEFAULT {
+        signature.push(sighash_type);
+    }
+
+    witness.insert(0, signature);
+    witness.extend(annex.map(<[u8]>::to_vec));
+    tx.inputs[index].witness = witness;
+
+    Ok(())
+}
+
+/// Checks that there is one UTXO for each input of a transaction.
+fn check_utxos(tx: &Transaction, utxos: &[Utxo]) -> Result<(), SigningError> {
+    match utxos.len() == tx.inputs.len() {
+        true => Ok(()),
+        false => Err(SigningError::UtxoCountMismatch { inputs: tx.inputs.len(), utxos: utxos.len() }),
+    }
+}
+
+fn is_taproot(utxo: &Utxo) -> bool {
+    matches!(
+        Address::from_script_pubkey(&utxo.script_pubkey, Default::default()),
+        Some(Address { address_type: AddressType::P2tr, .. })
+    )
+}
+
+/// Checks that a control block proves a leaf script to be committed to by an output key, as
+/// BIP341 does when spending through the script path.
+///
+/// # Return
+///
+/// * The TapLeaf hash of the script, `None` if the control block is invalid.
+fn verify_control_block(leaf_script: &[u8], control_block: &[u8], output_key: &[u8]) -> Option<Vec<u8>> {
+    let path_size = control_block.len().checked_sub(CONTROL_BLOCK_BASE_SIZE)?;
+
+    if path_size % CONTROL_BLOCK_NODE_SIZE != 0 || path_size / CONTROL_BLOCK_NODE_SIZE > CONTROL_BLOCK_MAX_NODES {
+        return None;
+    }
+
+    let leaf_hash = tap_leaf_hash(control_block[0] & 0xfe, leaf_script);
+    let internal_key = &control_block[1..CONTROL_BLOCK_BASE_SIZE];
+    let merkle_root = control_block[CONTROL_BLOCK_BASE_SIZE..]
+        .chunks(CONTROL_BLOCK_NODE_SIZE)
+        .fold(leaf_hash.clone(), |node, sibling| tap_branch_hash(&node, sibling));
+
+    let (tweaked_key, odd) = taproot_tweak_public_key(internal_key, Some(&merkle_root)).ok()?;
+
+    match tweaked_key == output_key && odd == (control_block[0] & 0x01 == 0x01) {
+        true => Some(leaf_hash),
+        false => None,
+    }
+}
+
+/// Returns the first key, and its serialized public key, whose public key matches.
+///
+/// # Arguments
+///
+/// * `uncompressed` - Whether to also try the uncompressed public keys, which segwit forbids.
+///
+/// # Return
+///
+/// * The key found, `None` if none matches, or an error if a key tried is not a valid private key.
+fn find_key(
+    keys: &[PrivateKey],
+    uncompressed: bool,
+    matches: impl Fn(Vec<u8>) -> bool,
+) -> Result<Option<(&PrivateKey, Vec<u8>)>, SigningError> {
+    for private_key in keys {
+        SecretKey::from_slice(&private_key.key).map_err(EcdsaError::InvalidPrivateKey)?;
+
+        let public_key = PublicKey::from_private_key(private_key.clone());
+        let mut candidates = vec![public_key.compressed];
+
+        if uncompressed {
+            candidates.push(public_key.uncompressed);
+        }
+
+        if let Some(public_key) = candidates.into_iter().find(|public_key| matches(public_key.clone())) {
+            return Ok(Some((private_key, public_key)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns the P2WPKH witness program of a public key, the redeem script of P2SH-P2WPKH.
+fn p2wpkh_script(public_key: Vec<u8>) -> Vec<u8> {
+    [&[0x00, 0x14], &public_key.hash160()[..]].concat()
+}
+
+/// Signs a sighash with ECDSA, rejecting the sighash types Bitcoin Core does not relay.
+fn sign(private_key: &PrivateKey, sighash: &[u8], sighash_type: u8) -> Result<Vec<u8>, SigningError> {
+    if !matches!(sighash_type, 0x01..=0x03 | 0x81..=0x83) {
+        return Err(SighashError::InvalidSighashType(sighash_type).into());
+    }
+
+    let mut signature = ecdsa::sign(private_key, sighash)?;
+    signature.sighash_type = Some(sighash_type);
+    Ok(signature.encode())
+}
+
+/// Returns a script pushing each item, which are all signatures, keys or redeem scripts.
+fn push_all(items: &[&[u8]]) -> Vec<u8> {
+    let mut script = Vec::new();
+
+    for item in items {
+        push_data(&mut script, minimal_push_opcode(item.len()), item).expect("items are at most 75 bytes");
+    }
+
+    script
+}
+
+#[cfg(test)]
+mod signer_tests {
+    use super::*;
+    use crate::key::{
+        ADDRESS_FROM_COMPRESSED, ADDRESS_FROM_UNCOMPRESSED, P2TR_ADDRESS, P2WPKH_ADDRESS, PRIVATE_KEY,
+        TAPROOT_LEAF_TAPSCRIPT,
+    };
+    use crate::network::Network;
+    use crate::transaction::sighash::ANNEX_TAG;
+    use crate::transaction::display_txid;
+    use crate::transaction::test_vectors::{
+        BIP143_P2SH_P2WPKH_TX, BIP143_P2WPKH_TX, BIP341_TX, BIP341_UTXOS, P2PK_PUBLIC_KEY,
+    };
+
+    const TXID: &str = "fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f";
+
+    fn utxo(address: &str, vout: u32, value: u64) -> Utxo {
+        let script_pubkey = hex::encode(Address::from_str(address).unwrap().script_pubkey());
+
+        Utxo::from_str(&format!("{}:{}:{}:{}", TXID, vout, value, script_pubkey)).unwrap()
+    }
+
+    fn destination() -> (Address, u64) {
+        (Address::p2pkh(vec![0x11; 20], Network::Mainnet), 90_000)
+    }
+
+    #[test]
+    fn should_parse_utxos() {
+        let utxo = utxo(ADDRESS_FROM_COMPRESSED, 1, 100_000);
+
+        assert_eq!(display_txid(&utxo.txid), TXID);
+        assert_eq!((utxo.vout, utxo.value), (1, 100_000));
+        assert_eq!(utxo.script_pubkey.len(), 25);
+
+        let invalid = [
+            format!("{}:1:100", TXID),
+            format!("{}:x:100:00", TXID),
+            format!("{}:1:{}:00", TXID, MAX_MONEY + 1),
+            format!("{}:1:{}:00", TXID, u64::MAX),
+            "00:1:100:00".to_string(),
+        ];
+
+        for utxo in invalid {
+            assert_eq!(Utxo::from_str(&utxo), Err(SigningError::InvalidUtxo(utxo.clone())));
+        }
+    }
+
+    #[test]
+    fn should_sign_p2pkh_inputs() {
+        let utxos = [utxo(ADDRESS_FROM_COMPRESSED, 0, 60_000), utxo(ADDRESS_FROM_UNCOMPRESSED, 1, 40_000)];
+        let private_key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let mut tx = build_transaction(&utxos, &[destination()]).unwrap();
+        sign_transaction(&mut tx, &utxos, &[private_key]).unwrap();
+
+        assert_eq!(
+            hex::encode(tx.serialize()),
+            "02000000029f96ade4b41d5433f4eda31e1738ec2b36f6e7d1420d94a6af99801a88f7f7ff000000006a47304402205b82bd760d26b3711ccfb1d2cb9beec7f3b65e3e706ad01d782b9bef593840ba022004a79405d478cdca1e84d1520981da0eb93d1364123ae9e1a5479712437b3391012103f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341affffffff9f96ade4b41d5433f4eda31e1738ec2b36f6e7d1420d94a6af99801a88f7f7ff010000008a473044022047a9e7ee192d156c696f765bfa9d1e102292cbf554c00a4332278e4867ef34b3022038b4d8b19df5a3280611d4c4fcae0f9b3ca1466c7755f0918f27e294352c95a6014104f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a07cf33da18bd734c600b96a72bbc4749d5141c90ec8ac328ae52ddfe2e505bdbffffffff01905f0100000000001976a914111111111111111111111111111111111111111188ac00000000"
+        );
+    }
+
+    /// Returns a transaction from BIP143 stripped of its scriptSigs and witnesses.
+    fn unsigned(signed: &str) -> Transaction {
+        let mut tx = Transaction::from_str(signed).unwrap();
+
+        for input in tx.inputs.iter_mut() {
+            input.script_sig = Vec::new();
+            input.witness = Vec::new();
+        }
+
+        tx
+    }
+
+    fn bip143_utxo(script_pubkey: &str, value: u64) -> Utxo {
+        Utxo { txid: Vec::new(), vout: 0, value, script_pubkey: hex::decode(script_pubkey).unwrap() }
+    }
+
+    #[test]
+    fn should_sign_p2wpkh_inputs() {
+        let mut tx = unsigned(BIP143_P2WPKH_TX);
+        let signed = Transaction::from_str(BIP143_P2WPKH_TX).unwrap();
+        let utxos = [
+            bip143_utxo(&format!("21{}ac", P2PK_PUBLIC_KEY), 625_000_000),
+            bip143_utxo("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1", 600_000_000),
+        ];
+        let private_key =
+            PrivateKey::from_str("619c335025c7f4012e556c2a58b2506e30b8511b53ade95ea316fd8c3286feb9").unwrap();
+
+        sign_input(&mut tx, 1, &utxos, &[private_key], SIGHASH_ALL).unwrap();
+        tx.inputs[0].script_sig = signed.inputs[0].script_sig.clone();
+
+        assert!(tx.inputs[1].script_sig.is_empty());
+        assert_eq!(hex::encode(tx.serialize()), BIP143_P2WPKH_TX);
+    }
+
+    #[test]
+    fn should_sign_p2sh_p2wpkh_inputs() {
+        let mut tx = unsigned(BIP143_P2SH_P2WPKH_TX);
+        let utxo = bip143_utxo("a9144733f37cf4db86fbc2efed2500b4f4e49f31202387", 1_000_000_000);
+        let private_key =
+            PrivateKey::from_str("eb696a065ef48a2192da5b28b694f87544b30fae8327c4510137a922f32c6dcf").unwrap();
+
+        sign_transaction(&mut tx, &[utxo], &[private_key]).unwrap();
+
+        assert_eq!(hex::encode(tx.serialize()), BIP143_P2SH_P2WPKH_TX);
+    }
+
+    #[test]
+    fn should_sign_with_every_sighash_type() {
+        let utxos = [utxo(P2WPKH_ADDRESS, 0, 60_000), utxo(ADDRESS_FROM_COMPRESSED, 1, 40_000)];
+        let keys = [PrivateKey::from_str(PRIVATE_KEY).unwrap()];
+        let public_key = PublicKey::from_private_key(keys[0].clone()).compressed;
+        let tx = build_transaction(&utxos, &[destination()]).unwrap();
+
+        for sighash_type in [0x01, 0x02, 0x03, 0x81, 0x82, 0x83] {
+            let mut tx = tx.clone();
+            sign_input(&mut tx, 0, &utxos, &keys, sighash_type).unwrap();
+            sign_input(&mut tx, 1, &utxos, &keys, sighash_type).unwrap();
+
+            let script_code = utxos[1].script_pubkey.clone();
+            let witness_signature = &tx.inputs[0].witness[0];
+            let legacy_signature = &tx.inputs[1].script_sig[1..tx.inputs[1].script_sig[0] as usize + 1];
+
+            assert_eq!(witness_signature.last(), Some(&sighash_type));
+            assert_eq!(legacy_signature.last(), Some(&sighash_type));
+
+            let sighash = segwit_v0_sighash(&tx, 0, &script_code, 60_000, sighash_type);
+            assert_eq!(ecdsa::verify(&public_key, &sighash, witness_signature), Ok(()));
+
+            let sighash = legacy_sighash(&tx, 1, &script_code, sighash_type);
+            assert_eq!(ecdsa::verify(&public_key, &sighash, legacy_signature), Ok(()));
+        }
+    }
+
+    #[test]
+    fn should_throw_error_if_sighash_type_is_invalid() {
+        let utxos = [utxo(P2WPKH_ADDRESS, 0, 60_000), utxo(ADDRESS_FROM_COMPRESSED, 1, 40_000)];
+        let keys = [PrivateKey::from_str(PRIVATE_KEY).unwrap()];
+        let mut tx = build_transaction(&utxos, &[destination()]).unwrap();
+
+        for sighash_type in [SIGHASH_DEFAULT, 0x04, 0x80, 0x84] {
+            let error = Err(SigningError::InvalidSighash(SighashError::InvalidSighashType(sighash_type)));
+
+            assert_eq!(sign_input(&mut tx, 0, &utxos, &keys, sighash_type), error);
+            assert_eq!(sign_input(&mut tx, 1, &utxos, &keys, sighash_type), error);
+        }
+
+        assert!(tx.inputs.iter().all(|input| input.script_sig.is_empty() && input.witness.is_empty()));
+    }
+
+    #[test]
+    fn should_throw_error_if_transaction_cannot_be_signed() {
+        let utxos = [utxo(ADDRESS_FROM_COMPRESSED, 0, 60_000)];
+        let keys = [PrivateKey::from_str(PRIVATE_KEY).unwrap()];
+
+        assert_eq!(
+            build_transaction(&utxos, &[destination()]),
+            Err(SigningError::InsufficientFunds { inputs: 60_000, outputs: 90_000 })
+        );
+        assert_eq!(build_transaction(&[], &[destination()]), Err(SigningError::NoInputs));
+        assert_eq!(
+            build_transaction(&utxos, &[(destination().0, MAX_MONEY), (destination().0, 1)]),
+            Err(SigningError::AmountOutOfRange)
+        );
+        assert_eq!(
+            build_transaction(&[utxos[0].clone(), Utxo { value: MAX_MONEY, ..utxos[0].clone() }], &[destination()]),
+            Err(SigningError::AmountOutOfRange)
+        );
+        assert_eq!(build_transaction(&utxos, &[]), Err(SigningError::NoOutputs));
+
+        let utxos = [utxo(ADDRESS_FROM_COMPRESSED, 0, 100_000)];
+        let mut tx = build_transaction(&utxos, &[destination()]).unwrap();
+
+        assert_eq!(sign_transaction(&mut tx, &utxos, &[]), Err(SigningError::MissingKey(0)));
+        assert_eq!(
+            sign_transaction(&mut tx, &utxos, &[PrivateKey::from_hex_or_wif("0").unwrap()]),
+            Err(SigningError::EcdsaSigningFailed(EcdsaError::InvalidPrivateKey(secp256k1::Error::InvalidSecretKey)))
+        );
+        assert_eq!(
+            sign_transaction(&mut tx, &[utxos[0].clone(), utxos[0].clone()], &keys),
+            Err(SigningError::UtxoCountMismatch { inputs: 1, utxos: 2 })
+        );
+        assert_eq!(sign_transaction(&mut tx, &[], &keys), Err(SigningError::UtxoCountMismatch { inputs: 1, utxos: 0 }));
+        assert_eq!(sign_input(&mut tx, 1, &utxos, &keys, SIGHASH_ALL), Err(SigningError::InvalidInputIndex(1)));
+
+        let p2sh_script = Address::p2sh(vec![0x11; 20], Network::Mainnet).script_pubkey();
+        let p2wsh_script = Address::segwit(0, vec![0x11; 32], Network::Mainnet).script_pubkey();
+        let p2sh = Utxo { script_pubkey: p2sh_script, ..utxos[0].clone() };
+        let p2wsh = Utxo { script_pubkey: p2wsh_script, ..utxos[0].clone() };
+
+        assert_eq!(sign_transaction(&mut tx, &[p2sh], &keys), Err(SigningError::MissingKey(0)));
+        assert_eq!(sign_transaction(&mut tx, &[p2wsh], &keys), Err(SigningError::UnsupportedScript(0)));
+    }
+
+    fn bip341_utxos() -> Vec<Utxo> {
+        BIP341_UTXOS.iter().map(|(value, script_pubkey)| bip143_utxo(script_pubkey, *value)).collect()
+    }
+
+    /// The keyPathSpending vectors of BIP341, whose signatures use all-zero auxiliary randomness.
+    #[test]
+    fn should_sign_taproot_key_path_inputs() {
+        let utxos = bip341_utxos();
+        let tx = Transaction::from_str(BIP341_TX).unwrap();
+
+        let vectors = [
+            (
+                0,
+                "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa",
+                None,
+                0x03,
+                "ed7c1647cb97379e76892be0cacff57ec4a7102aa24296ca39af7541246d8ff14d38958d4cc1e2e478e4d4a764bbfd835b16d4e314b72937b29833060b87276c03",
+            ),
+            (
+                1,
+                "1e4da49f6aaf4e5cd175fe08a32bb5cb4863d963921255f33d3bc31e1343907f",
+                Some("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"),
+                0x83,
+                "052aedffc554b41f52b521071793a6b88d6dbca9dba94cf34c83696de0c1ec35ca9c5ed4ab28059bd606a4f3a657eec0bb96661d42921b5f50a95ad33675b54f83",
+            ),
+            (
+                3,
+                "d3c7af07da2d54f7a7735d3d0fc4f0a73164db638b2f2f7c43f711f6d4aa7e64",
+                Some("c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b"),
+                0x01,
+                "ff45f742a876139946a149ab4d9185574b98dc919d2eb6754f8abaa59d18b025637a3aa043b91817739554f4ed2026cf8022dbd83e351ce1fabc272841d2510a01",
+            ),
+            (
+                4,
+                "f36bb07a11e469ce941d16b63b11b9b9120a84d9d87cff2c84a8d4affb438f4e",
+                Some("ccbd66c6f7e8fdab47b3a486f59d28262be857f30d4773f2d5ea47f7761ce0e2"),
+                0x00,
+                "b4010dd48a617db09926f729e79c33ae0b4e94b79f04a1ae93ede6315eb3669de185a17d2b0ac9ee09fd4c64b678a0b61a0a86fa888a273c8511be83bfd6810f",
+            ),
+            (
+                6,
+                "415cfe9c15d9cea27d8104d5517c06e9de48e2f986b695e4f5ffebf230e725d8",
+                Some("2f6b2c5397b6d68ca18e09a3f05161668ffe93a988582d55c6f07bd5b3329def"),
+                0x02,
+                "a3785919a2ce3c4ce26f298c3d51619bc474ae24014bcdd31328cd8cfbab2eff3395fa0a16fe5f486d12f22a9cedded5ae74feb4bbe5351346508c5405bcfee002",
+            ),
+            (
+                7,
+                "c7b0e81f0a9a0b0499e112279d718cca98e79a12e2f137c72ae5b213aad0d103",
+                Some("6c2dc106ab816b73f9d07e3cd1ef2c8c1256f519748e0813e4edd2405d277bef"),
+                0x82,
+                "ea0c6ba90763c2d3a296ad82ba45881abb4f426b3f87af162dd24d5109edc1cdd11915095ba47c3a9963dc1e6c432939872bc49212fe34c632cd3ab9fed429c482",
+            ),
+            (
+                8,
+                "77863416be0d0665e517e1c375fd6f75839544eca553675ef7fdf4949518ebaa",
+                Some("ab179431c28d3b68fb798957faf5497d69c883c6fb1e1cd9f81483d87bac90cc"),
+                0x81,
+                "bbc9584a11074e83bc8c6759ec55401f0ae7b03ef290c3139814f545b58a9f8127258000874f44bc46db7646322107d4d86aec8e73b8719a61fff761d75b5dd981",
+            ),
+        ];
+
+        for (index, private_key, merkle_root, sighash_type, witness) in vectors {
+            let mut tx = tx.clone();
+            let private_key = PrivateKey::from_str(private_key).unwrap();
+            let spend = TaprootSpend::KeyPath { merkle_root: merkle_root.map(|root| hex::decode(root).unwrap()) };
+
+            sign_taproot_input(&mut tx, index, &utxos, &private_key, &spend, sighash_type, None, Some(&[0x00; 32]))
+                .unwrap();
+
+            assert_eq!(tx.inputs[index].witness, vec![hex::decode(witness).unwrap()]);
+        }
+    }
+
+    #[test]
+    fn should_sign_bip86_inputs() {
+        let utxos = [utxo(P2TR_ADDRESS, 0, 60_000), utxo(P2WPKH_ADDRESS, 1, 40_000)];
+        let keys = [PrivateKey::from_str(PRIVATE_KEY).unwrap()];
+
+        let mut tx = build_transaction(&utxos, &[destination()]).unwrap();
+        sign_transaction(&mut tx, &utxos, &keys).unwrap();
+
+        let prevouts: Vec<TxOut> = utxos.iter().map(TxOut::from).collect();
+        let sighash = taproot_sighash(&tx, 0, &prevouts, SIGHASH_DEFAULT, None, None).unwrap();
+
+        assert_eq!(tx.inputs[0].witness.len(), 1);
+        assert_eq!(schnorr::verify(&utxos[0].script_pubkey[2..], &sighash, &tx.inputs[0].witness[0]), Ok(()));
+        assert_eq!(tx.inputs[1].witness[0].last(), Some(&SIGHASH_ALL));
+    }
+
+    /// Returns a script checking a signature against an x-only key, the usual tapscript leaf.
+    fn checksig_script(private_key: &PrivateKey) -> Vec<u8> {
+        [&[0x20][..], &schnorr::x_only_public_key(private_key), &[0xac]].concat()
+    }
+
+    #[test]
+    fn should_sign_taproot_script_path_inputs() {
+        let internal_key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+        let leaf_key =
+            PrivateKey::from_str("6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa").unwrap();
+        let leaf_script = checksig_script(&leaf_key);
+        let sibling_hash = tap_leaf_hash(TAPROOT_LEAF_TAPSCRIPT, &checksig_script(&internal_key));
+        let leaf_hash = tap_leaf_hash(TAPROOT_LEAF_TAPSCRIPT, &leaf_script);
+
+        let internal_key = schnorr::x_only_public_key(&internal_key);
+        let merkle_root = tap_branch_hash(&leaf_hash, &sibling_hash);
+        let (output_key, odd) = taproot_tweak_public_key(&internal_key, Some(&merkle_root)).unwrap();
+        let control_block = [&[TAPROOT_LEAF_TAPSCRIPT | odd as u8][..], &internal_key, &sibling_hash].concat();
+
+        let address = Address::segwit(1, output_key.clone(), Network::Mainnet);
+        let utxos = [utxo(&address.to_string(), 0, 60_000), utxo(P2WPKH_ADDRESS, 1, 40_000)];
+        let prevouts: Vec<TxOut> = utxos.iter().map(TxOut::from).collect();
+        let tx = build_transaction(&utxos, &[destination()]).unwrap();
+        let spend = TaprootSpend::ScriptPath { leaf_script: leaf_script.clone(), control_block: control_block.clone() };
+
+        let mut signed = tx.clone();
+        sign_taproot_input(&mut signed, 0, &utxos, &leaf_key, &spend, SIGHASH_DEFAULT, None, None).unwrap();
+
+        let sighash = taproot_sighash(&signed, 0, &prevouts, SIGHASH_DEFAULT, None, Some(&leaf_hash)).unwrap();
+        let witness = &signed.inputs[0].witness;
+
+        assert_eq!(witness[1..], [leaf_script.clone(), control_block.clone()]);
+        assert_eq!(schnorr::verify(&schnorr::x_only_public_key(&leaf_key), &sighash, &witness[0]), Ok(()));
+
+        let annex = [ANNEX_TAG, 0x01, 0x02];
+        let mut signed = tx.clone();
+        sign_taproot_input(&mut signed, 0, &utxos, &leaf_key, &spend, 0x83, Some(&annex), None).unwrap();
+
+        let sighash = taproot_sighash(&signed, 0, &prevouts, 0x83, Some(&annex), Some(&leaf_hash)).unwrap();
+        let witness = &signed.inputs[0].witness;
+
+        assert_eq!(witness[1..], [leaf_script, control_block, annex.to_vec()]);
+        assert_eq!(witness[0].last(), Some(&0x83));
+        assert_eq!(schnorr::verify(&schnorr::x_only_public_key(&leaf_key), &sighash, &witness[0][..64]), Ok(()));
+    }
+
+    #[test]
+    fn should_throw_error_if_taproot_input_cannot_be_signed() {
+        let private_key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+        let leaf_script = checksig_script(&private_key);
+        let internal_key = schnorr::x_only_public_key(&private_key);
+        let utxos = [utxo(P2TR_ADDRESS, 0, 60_000), utxo(P2WPKH_ADDRESS, 1, 40_000)];
+        let mut tx = build_transaction(&utxos, &[destination()]).unwrap();
+
+        let wrong_root = TaprootSpend::KeyPath { merkle_root: Some(vec![0x11; 32]) };
+        let short_control_block = TaprootSpend::ScriptPath {
+            leaf_script: leaf_script.clone(),
+            control_block: vec![TAPROOT_LEAF_TAPSCRIPT; 32],
+        };
+        let wrong_leaf = TaprootSpend::ScriptPath {
+            leaf_script,
+            control_block: [&[TAPROOT_LEAF_TAPSCRIPT][..], &internal_key].concat(),
+        };
+        let key_path = TaprootSpend::KeyPath { merkle_root: None };
+
+        assert_eq!(
+            sign_taproot_input(&mut tx, 0, &utxos, &private_key, &wrong_root, SIGHASH_DEFAULT, None, None),
+            Err(SigningError::MissingKey(0))
+        );
+        assert_eq!(
+            sign_taproot_input(&mut tx, 0, &utxos, &private_key, &short_control_block, SIGHASH_DEFAULT, None, None),
+            Err(SigningError::InvalidControlBlock(0))
+        );
+        assert_eq!(
+            sign_taproot_input(&mut tx, 0, &utxos, &private_key, &wrong_leaf, SIGHASH_DEFAULT, None, None),
+            Err(SigningError::InvalidControlBlock(0))
+        );
+        assert_eq!(
+            sign_taproot_input(&mut tx, 1, &utxos, &private_key, &key_path, SIGHASH_DEFAULT, None, None),
+            Err(SigningError::UnsupportedScript(1))
+        );
+        assert_eq!(
+            sign_taproot_input(&mut tx, 0, &utxos, &private_key, &key_path, 0x84, None, None),
+            Err(SigningError::InvalidSighash(SighashError::InvalidSighashType(0x84)))
+        );
+        assert_eq!(
+            sign_taproot_input(&mut tx, 0, &utxos, &private_key, &key_path, SIGHASH_DEFAULT, None, Some(&[0x00; 31])),
+            Err(SigningError::SchnorrSigningFailed(SchnorrError::InvalidAuxRandLength(31)))
+        );
+        assert_eq!(
+            sign_taproot_input(&mut tx, 0, &utxos[..1], &private_key, &key_path, SIGHASH_DEFAULT, None, None),
+            Err(SigningError::UtxoCountMismatch { inputs: 2, utxos: 1 })
+        );
+        assert_eq!(
+            sign_transaction(&mut tx, &utxos, &[PrivateKey::from_str(&"11".repeat(32)).unwrap()]),
+            Err(SigningError::MissingKey(0))
+        );
+    }
+}
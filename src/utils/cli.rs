@@ -1,5 +1,18 @@
-use crate::key::{PublicKey, PrivateKey};
-use crate::base58decoder::base58decode;
+use crate::account::{Account, AccountKey, Purpose};
+use crate::address::Address;
+use crate::key::{convert_extended_key, tagged_hash, DerivationPath, ExtendedKeyError, ExtendedPrivateKey, ExtendedPublicKey, PublicKey, PrivateKey};
+use crate::base58decoder::{base58decode_unchecked, Payload};
+use crate::descriptor::{descriptor_checksum, Descriptor};
+use crate::bech32::{self, Variant};
+use crate::message::bip322::{self, Bip322Format};
+use crate::message::{sign_message, verify_message, MessageAddressType};
+use crate::mnemonic::{Language, Mnemonic};
+use crate::network::Network;
+use crate::script::Script;
+use crate::signature::der::{sighash_type_name, DerSignature};
+use crate::signature::{ecdsa, schnorr};
+use crate::transaction::signer::{build_transaction, sign_transaction, total_amount, Utxo, MAX_MONEY};
+use crate::transaction::{display_txid, Transaction};
 
 use clap::{Args, Parser, Subcommand};
 
@@ -8,6 +21,10 @@ use clap::{Args, Parser, Subcommand};
 struct Cli {
     #[clap(subcommand)]
     commands: Commands,
+
+    /// Network used to encode keys and addresses: mainnet, testnet, signet or regtest.
+    #[clap(long, global = true, value_parser, default_value_t = Network::Mainnet)]
+    network: Network,
 }
 
 #[derive(Debug, Subcommand)]
@@ -18,9 +35,30 @@ enum Commands {
     /// Logs the address derived from a uncompressed public key, given the private key.
     GetUncompressedAddressFrom(PrivKeyArg),
 
+    /// Logs the native segwit (P2WPKH) address derived from the compressed public key, given the private key.
+    GetSegwitAddressFrom(PrivKeyArg),
+
+    /// Logs the nested segwit (P2SH-P2WPKH) address derived from the compressed public key, given the private key.
+    GetNestedSegwitAddressFrom(PrivKeyArg),
+
+    /// Logs the taproot internal key, output key and (P2TR) address, given the private key.
+    GetTaprootAddressFrom(PrivKeyArg),
+
     /// Logs the public key coordinates, given the private key.
     GetCoordinatesFrom(PrivKeyArg),
 
+    /// Logs the compressed encoding of a public key.
+    CompressPublicKey(PubKeyArg),
+
+    /// Logs the uncompressed encoding of a public key.
+    DecompressPublicKey(PubKeyArg),
+
+    /// Logs the coordinates of a public key.
+    GetCoordinatesFromPublicKey(PubKeyArg),
+
+    /// Logs every address type derived from a public key.
+    GetAddressesFromPublicKey(PubKeyArg),
+
     /// Generates and logs an address from a random private key.
     GetAddress,
 
@@ -30,6 +68,9 @@ enum Commands {
         prefix: String,
     },
 
+    /// Logs the private key as a hex string
+    GetHex(PrivKeyArg),
+
     /// Logs the compressed private key as a hex string
     GetHexCompressed(PrivKeyArg),
 
@@ -39,57 +80,524 @@ enum Commands {
     /// Logs the private key using the "Compressed Wallet Import Format"
     GetWifCompressed(PrivKeyArg),
 
-    /// Decodes and logs the provided input
+    /// Derives and logs the BIP32 keys at a path, from a seed or an extended key
+    Derive {
+        /// Seed as a hex string, or an extended private or public key such as an xprv or xpub
+        #[clap(value_parser)]
+        key: String,
+
+        /// Derivation path relative to the given key, e.g. m/84'/0'/0'/0/5
+        #[clap(value_parser, default_value = "m")]
+        path: String,
+    },
+
+    /// Re-encodes an extended key under another SLIP-132 prefix, e.g. an xpub as a zpub
+    ConvertExtendedKey {
+        #[clap(value_parser)]
+        key: String,
+
+        /// Target prefix: xpub, ypub, zpub, Ypub, Zpub, tpub, upub, vpub, Upub, Vpub, or the
+        /// private counterpart of any of them
+        #[clap(value_parser)]
+        prefix: String,
+    },
+
+    /// Derives and logs the receive and change addresses of the standard accounts of a wallet
+    ScanAccount {
+        /// Seed as a hex string, mnemonic phrase, or account extended key such as a zpub
+        #[clap(value_parser)]
+        key: String,
+
+        /// Account purpose: 44, 49, 84 or 86. Defaults to every purpose for seeds and mnemonics,
+        /// and to the one implied by the prefix of extended keys
+        #[clap(long, value_parser)]
+        purpose: Option<Purpose>,
+
+        /// Account index, for seeds and mnemonics
+        #[clap(long, value_parser, default_value_t = 0)]
+        account: u32,
+
+        /// First address index of each chain
+        #[clap(long, value_parser, default_value_t = 0)]
+        start: u32,
+
+        /// Number of addresses of each chain
+        #[clap(long, value_parser, default_value_t = 5)]
+        count: u32,
+
+        /// Also logs the private keys in the "Wallet Import Format"
+        #[clap(long, value_parser)]
+        wif: bool,
+
+        /// Passphrase of the mnemonic
+        #[clap(long, value_parser, default_value = "")]
+        passphrase: String,
+    },
+
+    /// Checks an output descriptor and logs its checksum, scriptPubKeys and addresses
+    Descriptor {
+        /// Descriptor such as "wpkh([d34db33f/84'/0'/0']xpub.../0/*)", with or without its checksum
+        #[clap(value_parser)]
+        descriptor: String,
+
+        /// First index of ranged descriptors
+        #[clap(long, value_parser, default_value_t = 0)]
+        start: u32,
+
+        /// Number of indexes of ranged descriptors
+        #[clap(long, value_parser, default_value_t = 5)]
+        count: u32,
+    },
+
+    /// Signs a message with the "Bitcoin Signed Message" scheme and logs the base64 signature
+    SignMessage {
+        /// Private key as a hex or WIF string. Uncompressed WIF keys sign for uncompressed P2PKH addresses
+        #[clap(value_parser)]
+        private_key: String,
+
+        #[clap(value_parser)]
+        message: String,
+
+        /// Address type committed to by the signature: p2pkh, p2sh-p2wpkh or p2wpkh
+        #[clap(long, value_parser, default_value_t = MessageAddressType::P2pkh)]
+        address_type: MessageAddressType,
+
+        /// Uses the compressed P2PKH header byte for segwit addresses, as Electrum does
+        #[clap(long, value_parser)]
+        electrum: bool,
+    },
+
+    /// Verifies a message signed with the "Bitcoin Signed Message" scheme against an address
+    VerifyMessage {
+        #[clap(value_parser)]
+        address: String,
+
+        /// Base64 encoded signature
+        #[clap(value_parser)]
+        signature: String,
+
+        #[clap(value_parser)]
+        message: String,
+    },
+
+    /// Signs a message for any single key address with BIP322 and logs the base64 signature
+    Bip322Sign {
+        /// Private key as a hex or WIF string, the internal key for taproot addresses
+        #[clap(value_parser)]
+        private_key: String,
+
+        /// P2PKH, P2SH-P2WPKH, P2WPKH or P2TR address of the key
+        #[clap(value_parser)]
+        address: String,
+
+        #[clap(value_parser)]
+        message: String,
+
+        /// Signature format: simple, the witness only, or full, the whole to_sign transaction
+        #[clap(long, value_parser, default_value_t = Bip322Format::Simple)]
+        format: Bip322Format,
+    },
+
+    /// Verifies a BIP322 simple or full signature against an address
+    Bip322Verify {
+        #[clap(value_parser)]
+        address: String,
+
+        /// Base64 encoded signature
+        #[clap(value_parser)]
+        signature: String,
+
+        #[clap(value_parser)]
+        message: String,
+    },
+
+    /// Signs a 32-byte message with BIP340 and logs the x-only public key and the signature
+    SchnorrSign {
+        /// Private key as a hex or WIF string
+        #[clap(value_parser)]
+        private_key: String,
+
+        /// Message as a hex string, 32 bytes unless hashed with --tag
+        #[clap(value_parser)]
+        message: String,
+
+        /// 32 bytes of auxiliary randomness as a hex string, random by default
+        #[clap(long, value_parser)]
+        aux_rand: Option<String>,
+
+        /// Signs the BIP340 tagged hash of the message with this tag rather than the message itself
+        #[clap(long, value_parser)]
+        tag: Option<String>,
+    },
+
+    /// Verifies a BIP340 signature of a 32-byte message against an x-only public key
+    SchnorrVerify {
+        /// 32-byte x-only public key as a hex string
+        #[clap(value_parser)]
+        public_key: String,
+
+        /// Message as a hex string, 32 bytes unless hashed with --tag
+        #[clap(value_parser)]
+        message: String,
+
+        /// 64-byte signature as a hex string
+        #[clap(value_parser)]
+        signature: String,
+
+        /// Verifies the BIP340 tagged hash of the message with this tag rather than the message itself
+        #[clap(long, value_parser)]
+        tag: Option<String>,
+    },
+
+    /// Signs a 32-byte digest with ECDSA, using an RFC6979 nonce and a low S
+    EcdsaSign {
+        /// Private key as a hex string or WIF
+        #[clap(value_parser)]
+        private_key: String,
+
+        /// 32-byte digest as a hex string
+        #[clap(value_parser)]
+        digest: String,
+    },
+
+    /// Verifies a DER encoded ECDSA signature of a 32-byte digest against a public key
+    EcdsaVerify {
+        /// Compressed or uncompressed public key as a hex string
+        #[clap(value_parser)]
+        public_key: String,
+
+        /// 32-byte digest as a hex string
+        #[clap(value_parser)]
+        digest: String,
+
+        /// DER signature as a hex string, optionally followed by a sighash byte
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Decodes, validates and encodes strict DER signatures
+    Der {
+        #[clap(subcommand)]
+        command: DerCommands,
+    },
+
+    /// Decodes a raw legacy or segwit transaction and logs its fields, ids and size
+    DecodeTx {
+        /// Serialized transaction as a hex string
+        #[clap(value_parser)]
+        transaction: String,
+    },
+
+    /// Builds a transaction spending P2PKH, P2WPKH, P2SH-P2WPKH or BIP86 P2TR outputs, signs it
+    /// with SIGHASH_ALL, or SIGHASH_DEFAULT for P2TR, and logs its hex. Other sighash types,
+    /// annexes and taproot script-path spends are only available through the library
+    SignTx {
+        /// Output spent, as txid:vout:value:scriptPubKey with the value in satoshis, repeated
+        /// for each input
+        #[clap(long = "utxo", value_parser, required = true)]
+        utxos: Vec<String>,
+
+        /// Output created, as address:amount with the amount in satoshis, repeated for each output
+        #[clap(long = "to", value_parser, required = true)]
+        destinations: Vec<String>,
+
+        /// Private key as a hex string or WIF, repeated for each key signing
+        #[clap(long = "key", value_parser, required = true)]
+        keys: Vec<String>,
+    },
+
+    /// Generates, checks and derives seeds from BIP39 mnemonics
+    Mnemonic {
+        #[clap(subcommand)]
+        command: MnemonicCommands,
+    },
+
+    /// Validates a Base58 or bech32 address and logs what it encodes
+    ValidateAddress {
+        #[clap(value_parser)]
+        address: String,
+    },
+
+    /// Decodes the provided input and logs what it is along with its fields
     Base58Decode {
         #[clap(value_parser)]
         encoded: String,
-    }
+    },
+
+    /// Encodes hex data as bech32, or as a segwit address if a witness version is given
+    Bech32Encode {
+        /// Data to encode, as a hex string
+        #[clap(value_parser)]
+        data: String,
+
+        /// Human-readable part, defaults to the one of the selected network
+        #[clap(long, value_parser)]
+        hrp: Option<String>,
+
+        /// Witness version, from 0 to 16, of the witness program given as data
+        #[clap(long, value_parser)]
+        witness_version: Option<u8>,
+
+        /// Uses the bech32m checksum, implied by witness versions 1 and above
+        #[clap(long, value_parser)]
+        bech32m: bool,
+    },
+
+    /// Decodes and logs the provided bech32 or bech32m input
+    Bech32Decode {
+        #[clap(value_parser)]
+        encoded: String,
+    },
+
+    /// Assembles a script given in ASM, e.g. "OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG", and logs its hex
+    ScriptAsm {
+        #[clap(value_parser)]
+        asm: String,
+    },
+
+    /// Disassembles a script given as a hex string and logs its ASM
+    ScriptDisasm {
+        #[clap(value_parser)]
+        script: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum DerCommands {
+    /// Validates a signature against the strict DER rules of BIP66 and logs R, S and its sighash type
+    Decode {
+        /// DER signature as a hex string, optionally followed by a sighash byte
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Encodes R and S as a strict DER signature
+    Encode {
+        /// R as a hex string of up to 32 bytes
+        #[clap(value_parser)]
+        r: String,
+
+        /// S as a hex string of up to 32 bytes
+        #[clap(value_parser)]
+        s: String,
+
+        /// Sighash byte appended to the signature, as a hex string, e.g. 01 for SIGHASH_ALL
+        #[clap(long, value_parser)]
+        sighash_type: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum MnemonicCommands {
+    /// Generates a mnemonic from random entropy
+    New {
+        /// Number of words: 12, 15, 18, 21 or 24
+        #[clap(long, value_parser, default_value_t = 12)]
+        words: usize,
+
+        /// Wordlist language: english, japanese, korean, spanish, chinese-simplified,
+        /// chinese-traditional, french, italian, czech or portuguese
+        #[clap(long, value_parser, default_value_t = Language::English)]
+        language: Language,
+    },
+
+    /// Validates the words and checksum of a mnemonic and logs its language and entropy
+    Check {
+        #[clap(value_parser)]
+        phrase: String,
+    },
+
+    /// Logs the seed of a mnemonic and the BIP32 root key derived from it
+    ToSeed {
+        #[clap(value_parser)]
+        phrase: String,
+
+        /// Optional passphrase, also known as the 25th word
+        #[clap(long, value_parser, default_value = "")]
+        passphrase: String,
+    },
 }
 
 #[derive(Debug, Args)]
 struct PrivKeyArg {
+    /// Private key as a hex or WIF string
     #[clap(value_parser)]
     private_key: String,
 }
 
+#[derive(Debug, Args)]
+struct PubKeyArg {
+    /// Compressed or uncompressed public key as a hex string
+    #[clap(value_parser)]
+    public_key: String,
+}
+
 pub fn run() {
     let cli = Cli::parse();
+    let network = cli.network;
 
     match cli.commands {
-        Commands::GetCompressedAddressFrom(arg) => log_compressed_address(&arg.private_key),
-        Commands::GetUncompressedAddressFrom(arg) => log_uncompressed_address(&arg.private_key),
+        Commands::GetCompressedAddressFrom(arg) => log_compressed_address(&arg.private_key, network),
+        Commands::GetUncompressedAddressFrom(arg) => log_uncompressed_address(&arg.private_key, network),
+        Commands::GetSegwitAddressFrom(arg) => log_segwit_address(&arg.private_key, network),
+        Commands::GetNestedSegwitAddressFrom(arg) => log_nested_segwit_address(&arg.private_key, network),
+        Commands::GetTaprootAddressFrom(arg) => log_taproot_address(&arg.private_key, network),
         Commands::GetCoordinatesFrom(arg) => log_coordinates(&arg.private_key),
-        Commands::GetAddress => println!("{}", PublicKey::get_new_address()),
-        Commands::GetVanity { prefix } => println!("{}", PublicKey::vanity_address(&prefix)),
+        Commands::CompressPublicKey(arg) => log_compressed_public_key(&arg.public_key),
+        Commands::DecompressPublicKey(arg) => log_uncompressed_public_key(&arg.public_key),
+        Commands::GetCoordinatesFromPublicKey(arg) => log_public_key_coordinates(&arg.public_key),
+        Commands::GetAddressesFromPublicKey(arg) => log_public_key_addresses(&arg.public_key, network),
+        Commands::GetAddress => println!("{}", PublicKey::get_new_address(network)),
+        Commands::GetVanity { prefix } => println!("{}", PublicKey::vanity_address(&prefix, network)),
 
+        Commands::GetHex(arg) => log_hex_private_key(&arg.private_key),
         Commands::GetHexCompressed(arg) => log_hex_compressed_private_key(&arg.private_key),
-        Commands::GetWif(arg) => log_wif_format(&arg.private_key),
-        Commands::GetWifCompressed(arg) => log_wif_compressed_format(&arg.private_key),
+        Commands::GetWif(arg) => log_wif_format(&arg.private_key, network),
+        Commands::GetWifCompressed(arg) => log_wif_compressed_format(&arg.private_key, network),
+
+        Commands::Derive { key, path } => log_derived_keys(&key, &path, network),
+        Commands::ConvertExtendedKey { key, prefix } => log_converted_extended_key(&key, &prefix),
+
+        Commands::ScanAccount { key, purpose, account, start, count, wif, passphrase } => {
+            let range = start..start.saturating_add(count);
+            log_account_scan(&key, purpose, account, range, wif, &passphrase, network)
+        }
+
+        Commands::Descriptor { descriptor, start, count } => {
+            let range = start..start.saturating_add(count);
+            log_descriptor(&descriptor, range, network)
+        }
+
+        Commands::SignMessage { private_key, message, address_type, electrum } => {
+            log_signed_message(&private_key, &message, address_type, electrum, network)
+        }
+        Commands::VerifyMessage { address, signature, message } => {
+            log_message_verification(&address, &signature, &message)
+        }
+        Commands::Bip322Sign { private_key, address, message, format } => {
+            log_bip322_signed_message(&private_key, &address, &message, format)
+        }
+        Commands::Bip322Verify { address, signature, message } => {
+            log_bip322_verification(&address, &signature, &message)
+        }
+        Commands::SchnorrSign { private_key, message, aux_rand, tag } => {
+            log_schnorr_signature(&private_key, &message, aux_rand.as_deref(), tag.as_deref())
+        }
+        Commands::SchnorrVerify { public_key, message, signature, tag } => {
+            log_schnorr_verification(&public_key, &message, &signature, tag.as_deref())
+        }
+
+        Commands::EcdsaSign { private_key, digest } => log_ecdsa_signature(&private_key, &digest),
+        Commands::EcdsaVerify { public_key, digest, signature } => {
+            log_ecdsa_verification(&public_key, &digest, &signature)
+        }
+        Commands::Der { command } => match command {
+            DerCommands::Decode { signature } => log_der_decoding(&signature),
+            DerCommands::Encode { r, s, sighash_type } => log_der_encoding(&r, &s, sighash_type.as_deref()),
+        },
+        Commands::DecodeTx { transaction } => log_decoded_transaction(&transaction, network),
+        Commands::SignTx { utxos, destinations, keys } => log_signed_transaction(&utxos, &destinations, &keys),
+        Commands::Mnemonic { command } => match command {
+            MnemonicCommands::New { words, language } => log_new_mnemonic(words, language),
+            MnemonicCommands::Check { phrase } => log_mnemonic_check(&phrase),
+            MnemonicCommands::ToSeed { phrase, passphrase } => log_mnemonic_seed(&phrase, &passphrase, network),
+        },
 
-        Commands::Base58Decode { encoded } => log_base58_decoded(&encoded)
+        Commands::ValidateAddress { address } => log_address_validation(&address),
+        Commands::Base58Decode { encoded } => log_base58_decoded(&encoded),
+        Commands::Bech32Encode { data, hrp, witness_version, bech32m } => {
+            let hrp = hrp.unwrap_or_else(|| network.hrp().to_string());
+            log_bech32_encoded(&data, &hrp, witness_version, bech32m)
+        }
+        Commands::Bech32Decode { encoded } => log_bech32_decoded(&encoded),
+
+        Commands::ScriptAsm { asm } => log_script_assembled(&asm),
+        Commands::ScriptDisasm { script } => log_script_disassembled(&script),
     }
 }
 
-fn log_compressed_address(private_key: &str) {
-    let k = PublicKey::from_private_key_string(&private_key);
+fn log_compressed_address(private_key: &str, network: Network) {
+    let k = PublicKey::from_private_key_string(private_key);
 
     match k {
-        Ok(pubkey) => println!("{}", pubkey.get_address_from_compressed()),
+        Ok(pubkey) => println!("{}", pubkey.get_address_from_compressed(network)),
         Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
     }
 }
 
-fn log_uncompressed_address(private_key: &str) {
-    let k = PublicKey::from_private_key_string(&private_key);
+fn log_uncompressed_address(private_key: &str, network: Network) {
+    let k = PublicKey::from_private_key_string(private_key);
 
     match k {
-        Ok(pubkey) => println!("{}", pubkey.get_address_from_uncompressed()),
+        Ok(pubkey) => println!("{}", pubkey.get_address_from_uncompressed(network)),
         Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
     }
 }
 
+fn is_uncompressed_wif(private_key: &str) -> bool {
+    matches!(PrivateKey::from_wif(private_key), Ok((_, false)))
+}
+
+fn log_segwit_address(private_key: &str, network: Network) {
+    if is_uncompressed_wif(private_key) {
+        return eprintln!("Error getting segwit address: uncompressed WIF keys cannot be used in segwit outputs");
+    }
+
+    let k = PublicKey::from_private_key_string(private_key);
+
+    match k {
+        Ok(pubkey) => match pubkey.get_p2wpkh_address(network) {
+            Ok(address) => println!("{}", address),
+            Err(error) => eprintln!("Error getting segwit address: {:?}", error),
+        },
+        Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
+    }
+}
+
+fn log_nested_segwit_address(private_key: &str, network: Network) {
+    if is_uncompressed_wif(private_key) {
+        return eprintln!("Error getting nested segwit address: uncompressed WIF keys cannot be used in segwit outputs");
+    }
+
+    let k = PublicKey::from_private_key_string(private_key);
+
+    match k {
+        Ok(pubkey) => match pubkey.get_p2sh_p2wpkh_address(network) {
+            Ok(address) => println!("{}", address),
+            Err(error) => eprintln!("Error getting nested segwit address: {:?}", error),
+        },
+        Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
+    }
+}
+
+fn log_taproot_address(private_key: &str, network: Network) {
+    let k = PublicKey::from_private_key_string(private_key);
+
+    let pubkey = match k {
+        Ok(pubkey) => pubkey,
+        Err(error) => return eprintln!("Error getting address from private key string: {:?}", error),
+    };
+
+    let keys = (
+        pubkey.clone().get_x_only_public_key(),
+        pubkey.clone().get_p2tr_output_key(),
+        pubkey.get_p2tr_address(network),
+    );
+
+    match keys {
+        (Ok(internal_key), Ok(output_key), Ok(address)) => {
+            println!("Internal key: {}", hex::encode(internal_key));
+            println!("Output key: {}", hex::encode(output_key));
+            println!("Address: {}", address);
+        }
+        (Err(error), _, _) | (_, Err(error), _) | (_, _, Err(error)) => {
+            eprintln!("Error getting taproot address: {:?}", error)
+        }
+    }
+}
+
 fn log_coordinates(private_key: &str) {
-    let k = PublicKey::from_private_key_string(&private_key);
+    let k = PublicKey::from_private_key_string(private_key);
 
     match k {
         Ok(pubkey) => {
@@ -102,8 +610,85 @@ fn log_coordinates(private_key: &str) {
     }
 }
 
+fn log_compressed_public_key(public_key: &str) {
+    let k = PublicKey::from_hex(public_key);
+
+    match k {
+        Ok(pubkey) => println!("{}", hex::encode(pubkey.compressed)),
+        Err(error) => eprintln!("Error parsing public key: {:?}", error),
+    }
+}
+
+fn log_uncompressed_public_key(public_key: &str) {
+    let k = PublicKey::from_hex(public_key);
+
+    match k {
+        Ok(pubkey) => println!("{}", hex::encode(pubkey.uncompressed)),
+        Err(error) => eprintln!("Error parsing public key: {:?}", error),
+    }
+}
+
+fn log_public_key_coordinates(public_key: &str) {
+    let k = PublicKey::from_hex(public_key);
+
+    match k {
+        Ok(pubkey) => {
+            let (x, y) = pubkey.get_coordinates();
+
+            println!("x = {}", x);
+            println!("y = {}", y);
+        }
+        Err(error) => eprintln!("Error parsing public key: {:?}", error),
+    }
+}
+
+fn log_public_key_addresses(public_key: &str, network: Network) {
+    let k = PublicKey::from_hex(public_key);
+
+    let pubkey = match k {
+        Ok(pubkey) => pubkey,
+        Err(error) => return eprintln!("Error parsing public key: {:?}", error),
+    };
+
+    println!("P2PKH (compressed): {}", pubkey.clone().get_address_from_compressed(network));
+    println!("P2PKH (uncompressed): {}", pubkey.clone().get_address_from_uncompressed(network));
+
+    if public_key.len() == 130 {
+        println!("Segwit addresses skipped: the public key was given uncompressed");
+        return;
+    }
+
+    let segwit = [
+        ("P2WPKH", pubkey.clone().get_p2wpkh_address(network)),
+        ("P2SH-P2WPKH", pubkey.clone().get_p2sh_p2wpkh_address(network)),
+        ("P2TR", pubkey.get_p2tr_address(network)),
+    ];
+
+    for (address_type, address) in segwit {
+        match address {
+            Ok(address) => println!("{}: {}", address_type, address),
+            Err(error) => eprintln!("Error getting {} address: {:?}", address_type, error),
+        }
+    }
+}
+
+fn log_hex_private_key(private_key: &str) {
+    if let Ok((privkey, compressed)) = PrivateKey::from_wif(private_key) {
+        println!("Private key: {}", privkey.as_hex_string());
+        println!("Compressed: {}", compressed);
+        return;
+    }
+
+    let r = PrivateKey::from_hex_or_wif(private_key);
+
+    match r {
+        Ok(privkey) => println!("Private key: {}", privkey.as_hex_string()),
+        Err(error) => eprintln!("Error converting input to private key: {:?}", error),
+    }
+}
+
 fn log_hex_compressed_private_key(private_key: &str) {
-    let r = PrivateKey::from_str(private_key);
+    let r = PrivateKey::from_hex_or_wif(private_key);
 
     match r {
         Ok(privkey) => println!("Compressed public key: {}", privkey.as_hex_compressed_string()),
@@ -111,35 +696,708 @@ fn log_hex_compressed_private_key(private_key: &str) {
     }
 }
 
-fn log_wif_format(private_key: &str) {
-    let r = PrivateKey::from_str(private_key);
+fn log_wif_format(private_key: &str, network: Network) {
+    let r = PrivateKey::from_hex_or_wif(private_key);
 
     match r {
-        Ok(privkey) => println!("WIF: {}", privkey.as_wif()),
+        Ok(privkey) => println!("WIF: {}", privkey.as_wif(network)),
         Err(error) => eprintln!("Error converting input to private key: {:?}", error),
     }
 }
 
-fn log_wif_compressed_format(private_key: &str) {
-    let r = PrivateKey::from_str(private_key);
+fn log_wif_compressed_format(private_key: &str, network: Network) {
+    let r = PrivateKey::from_hex_or_wif(private_key);
 
     match r {
-        Ok(privkey) => println!("WIF compressed: {}", privkey.as_wif_compressed()),
+        Ok(privkey) => println!("WIF compressed: {}", privkey.as_wif_compressed(network)),
         Err(error) => eprintln!("Error converting input to private key: {:?}", error),
     }
 }
 
+fn log_derived_keys(key: &str, path: &str, network: Network) {
+    let path = match path.parse::<DerivationPath>() {
+        Ok(path) => path,
+        Err(error) => return eprintln!("Error parsing derivation path: {:?}", error),
+    };
+
+    let r = match key.chars().all(|c| c.is_ascii_hexdigit()) {
+        true => match hex::decode(key) {
+            Ok(seed) => ExtendedPrivateKey::from_seed(&seed, network).map(Some),
+            Err(error) => return eprintln!("Error decoding seed: {:?}", error),
+        },
+        false => match key.parse::<ExtendedPrivateKey>() {
+            Err(ExtendedKeyError::ExpectedPrivateKey) => Ok(None),
+            r => r.map(Some),
+        },
+    };
+
+    let derived = match r {
+        Ok(Some(xprv)) => xprv.derive_path(&path).map(|xprv| (Some(xprv.clone()), xprv.get_extended_public_key())),
+        Ok(None) => key
+            .parse::<ExtendedPublicKey>()
+            .and_then(|xpub| xpub.derive_path(&path))
+            .map(|xpub| (None, xpub)),
+        Err(error) => Err(error),
+    };
+
+    match derived {
+        Ok((xprv, xpub)) => {
+            println!("Path: {}", path);
+            println!("Fingerprint: {}", hex::encode(xpub.fingerprint()));
+
+            if let Some(xprv) = xprv {
+                println!("Extended private key: {}", xprv);
+                println!("Private key (WIF): {}", xprv.private_key.as_wif_compressed(xprv.network));
+            }
+
+            println!("Extended public key: {}", xpub);
+            println!("Public key: {}", hex::encode(&xpub.public_key.compressed));
+        }
+        Err(error) => eprintln!("Error deriving keys: {:?}", error),
+    }
+}
+
+fn log_converted_extended_key(key: &str, prefix: &str) {
+    let r = convert_extended_key(key, prefix);
+
+    match r {
+        Ok(key) => println!("{}", key),
+        Err(error) => eprintln!("Error converting extended key: {:?}", error),
+    }
+}
+
+fn log_account_scan(
+    key: &str,
+    purpose: Option<Purpose>,
+    index: u32,
+    range: std::ops::Range<u32>,
+    wif: bool,
+    passphrase: &str,
+    network: Network,
+) {
+    let purposes = match purpose {
+        Some(purpose) => vec![purpose],
+        None => Purpose::all().to_vec(),
+    };
+
+    let seed = match (key.contains(char::is_whitespace), key.chars().all(|c| c.is_ascii_hexdigit())) {
+        (true, _) => match Mnemonic::from_phrase(key) {
+            Ok(mnemonic) => Some(mnemonic.to_seed(passphrase)),
+            Err(error) => return eprintln!("Error parsing mnemonic: {:?}", error),
+        },
+        (false, true) => match hex::decode(key) {
+            Ok(seed) => Some(seed),
+            Err(error) => return eprintln!("Error decoding seed: {:?}", error),
+        },
+        (false, false) => None,
+    };
+
+    let accounts = match seed {
+        Some(seed) => purposes
+            .into_iter()
+            .map(|purpose| Account::from_seed(&seed, purpose, network, index))
+            .collect(),
+        None => {
+            let prefix = match base58decode_unchecked(key).map(|decoded| decoded.identify()) {
+                Ok(Payload::ExtendedKey { prefix, .. }) => prefix,
+                _ => return eprintln!("Error parsing key: expected a seed, mnemonic or extended key"),
+            };
+
+            let purpose = match purpose.or_else(|| Purpose::from_prefix(prefix)) {
+                Some(purpose) => purpose,
+                None => return eprintln!("Error parsing key: no purpose implied by {}, use --purpose", prefix),
+            };
+
+            let account_key = match key.parse::<ExtendedPrivateKey>() {
+                Ok(xprv) => Ok(AccountKey::Private(xprv)),
+                Err(ExtendedKeyError::ExpectedPrivateKey) => {
+                    key.parse::<ExtendedPublicKey>().map(AccountKey::Public)
+                }
+                Err(error) => Err(error),
+            };
+
+            account_key
+                .and_then(|account_key| Account::from_extended_key(account_key, purpose))
+                .map(|account| vec![account])
+        }
+    };
+
+    let accounts: Vec<Account> = match accounts {
+        Ok(accounts) => accounts,
+        Err(error) => return eprintln!("Error deriving account: {:?}", error),
+    };
+
+    for account in accounts {
+        println!("{} account {}", account.purpose, account.path);
+        println!("Extended public key: {}", account.get_extended_public_key());
+
+        let header = format!("{:<24} {:<66} {:<62} {}", "Path", "Public key", "Address", if wif { "WIF" } else { "" });
+        println!("{}", header.trim_end());
+
+        for change in [false, true] {
+            let addresses = match account.derive_addresses(change, range.clone()) {
+                Ok(addresses) => addresses,
+                Err(error) => return eprintln!("Error deriving addresses: {:?}", error),
+            };
+
+            for address in addresses {
+                let private_key = match (wif, address.private_key) {
+                    (true, Some(private_key)) => private_key.as_wif_compressed(account.network()),
+                    _ => String::new(),
+                };
+
+                let row = format!(
+                    "{:<24} {} {:<62} {}",
+                    address.path.to_string(),
+                    hex::encode(&address.public_key.compressed),
+                    address.address,
+                    private_key,
+                );
+
+                println!("{}", row.trim_end());
+            }
+        }
+
+        println!();
+    }
+}
+
+fn log_new_mnemonic(words: usize, language: Language) {
+    let r = Mnemonic::generate(words, language);
+
+    match r {
+        Ok(mnemonic) => println!("{}", mnemonic),
+        Err(error) => eprintln!("Error generating mnemonic: {:?}", error),
+    }
+}
+
+fn log_mnemonic_check(phrase: &str) {
+    let r = Mnemonic::from_phrase(phrase)
+        .and_then(|mnemonic| Ok((mnemonic.language, mnemonic.entropy()?)));
+
+    match r {
+        Ok((language, entropy)) => {
+            println!("Valid: true");
+            println!("Language: {}", language);
+            println!("Entropy: {}", hex::encode(entropy));
+        }
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
+fn log_mnemonic_seed(phrase: &str, passphrase: &str, network: Network) {
+    let mnemonic = match Mnemonic::from_phrase(phrase) {
+        Ok(mnemonic) => mnemonic,
+        Err(error) => return eprintln!("Error parsing mnemonic: {:?}", error),
+    };
+
+    let seed = mnemonic.to_seed(passphrase);
+
+    println!("Seed: {}", hex::encode(&seed));
+
+    match ExtendedPrivateKey::from_seed(&seed, network) {
+        Ok(xprv) => println!("Root key: {}", xprv),
+        Err(error) => eprintln!("Error deriving root key: {:?}", error),
+    }
+}
+
+fn log_descriptor(descriptor: &str, range: std::ops::Range<u32>, network: Network) {
+    let descriptor = match descriptor.parse::<Descriptor>() {
+        Ok(descriptor) => descriptor,
+        Err(error) => return eprintln!("Error parsing descriptor: {:?}", error),
+    };
+
+    let body = descriptor.to_string();
+
+    match descriptor_checksum(&body) {
+        Ok(checksum) => println!("Descriptor: {}#{}", body, checksum),
+        Err(error) => return eprintln!("Error computing checksum: {:?}", error),
+    }
+
+    let range = if descriptor.is_ranged() { range } else { 0..1 };
+
+    let header = format!("{:<10} {:<70} {}", "Index", "ScriptPubKey", "Address");
+    println!("{}", header.trim_end());
+
+    for index in range {
+        let script_pubkey = match descriptor.script_pubkey(index) {
+            Ok(script_pubkey) => script_pubkey,
+            Err(error) => return eprintln!("Error deriving script: {:?}", error),
+        };
+
+        let address = match descriptor.address(index, network) {
+            Ok(Some(address)) => address.to_string(),
+            Ok(None) => "-".to_string(),
+            Err(error) => return eprintln!("Error deriving address: {:?}", error),
+        };
+
+        println!("{:<10} {:<70} {}", index, script_pubkey.as_hex_string(), address);
+    }
+}
+
+fn log_signed_message(
+    private_key: &str,
+    message: &str,
+    address_type: MessageAddressType,
+    electrum: bool,
+    network: Network,
+) {
+    let address_type = match (is_uncompressed_wif(private_key), address_type) {
+        (true, MessageAddressType::P2pkh) => MessageAddressType::P2pkhUncompressed,
+        (true, MessageAddressType::P2shP2wpkh | MessageAddressType::P2wpkh) => {
+            return eprintln!("Error signing message: uncompressed WIF keys cannot be used in segwit outputs")
+        }
+        (_, address_type) => address_type,
+    };
+
+    let private_key = match PrivateKey::from_hex_or_wif(private_key) {
+        Ok(private_key) => private_key,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    let signature = match sign_message(&private_key, message, address_type, electrum) {
+        Ok(signature) => signature,
+        Err(error) => return eprintln!("Error signing message: {:?}", error),
+    };
+
+    let public_key = PublicKey::from_private_key(private_key);
+
+    println!("Address: {}", address_type.address(&public_key, network));
+    println!("Signature: {}", signature);
+}
+
+fn log_message_verification(address: &str, signature: &str, message: &str) {
+    match verify_message(address, signature, message) {
+        Ok(valid) => println!("Valid: {}", valid),
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
+fn log_bip322_signed_message(private_key: &str, address: &str, message: &str, format: Bip322Format) {
+    let private_key = match PrivateKey::from_hex_or_wif(private_key) {
+        Ok(private_key) => private_key,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    match bip322::sign_message(&private_key, address, message, format) {
+        Ok(signature) => println!("{}", signature),
+        Err(error) => eprintln!("Error signing message: {:?}", error),
+    }
+}
+
+fn log_bip322_verification(address: &str, signature: &str, message: &str) {
+    match bip322::verify_message(address, signature, message) {
+        Ok(()) => println!("Valid: true"),
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
+/// Decodes a hex message, replacing it with its tagged hash if a tag is given.
+fn schnorr_message(message: &str, tag: Option<&str>) -> Result<Vec<u8>, hex::FromHexError> {
+    let message = hex::decode(message)?;
+
+    Ok(match tag {
+        Some(tag) => tagged_hash(tag, &message),
+        None => message,
+    })
+}
+
+fn log_schnorr_signature(private_key: &str, message: &str, aux_rand: Option<&str>, tag: Option<&str>) {
+    let private_key = match PrivateKey::from_hex_or_wif(private_key) {
+        Ok(private_key) => private_key,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    let message = match schnorr_message(message, tag) {
+        Ok(message) => message,
+        Err(error) => return eprintln!("Error parsing message: {:?}", error),
+    };
+
+    let aux_rand = match aux_rand.map(hex::decode).transpose() {
+        Ok(aux_rand) => aux_rand,
+        Err(error) => return eprintln!("Error parsing auxiliary randomness: {:?}", error),
+    };
+
+    match schnorr::sign(&private_key, &message, aux_rand.as_deref()) {
+        Ok(signature) => {
+            println!("Public key: {}", hex::encode(schnorr::x_only_public_key(&private_key)));
+            println!("Signature: {}", hex::encode(signature));
+        }
+        Err(error) => eprintln!("Error signing message: {:?}", error),
+    }
+}
+
+fn log_schnorr_verification(public_key: &str, message: &str, signature: &str, tag: Option<&str>) {
+    let r = hex::decode(public_key).and_then(|public_key| {
+        Ok((public_key, schnorr_message(message, tag)?, hex::decode(signature)?))
+    });
+
+    let (public_key, message, signature) = match r {
+        Ok(decoded) => decoded,
+        Err(error) => return eprintln!("Error parsing input: {:?}", error),
+    };
+
+    match schnorr::verify(&public_key, &message, &signature) {
+        Ok(()) => println!("Valid: true"),
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
+fn log_ecdsa_signature(private_key: &str, digest: &str) {
+    let private_key = match PrivateKey::from_hex_or_wif(private_key) {
+        Ok(private_key) => private_key,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    let digest = match hex::decode(digest) {
+        Ok(digest) => digest,
+        Err(error) => return eprintln!("Error parsing digest: {:?}", error),
+    };
+
+    match ecdsa::sign(&private_key, &digest) {
+        Ok(signature) => {
+            println!("Public key: {}", hex::encode(PublicKey::from_private_key(private_key).compressed));
+            println!("Signature: {}", hex::encode(signature.encode()));
+            println!("R: {}", hex::encode(&signature.r));
+            println!("S: {}", hex::encode(&signature.s));
+        }
+        Err(error) => eprintln!("Error signing digest: {:?}", error),
+    }
+}
+
+fn log_ecdsa_verification(public_key: &str, digest: &str, signature: &str) {
+    let r = hex::decode(public_key).and_then(|public_key| {
+        Ok((public_key, hex::decode(digest)?, hex::decode(signature)?))
+    });
+
+    let (public_key, digest, signature) = match r {
+        Ok(decoded) => decoded,
+        Err(error) => return eprintln!("Error parsing input: {:?}", error),
+    };
+
+    match ecdsa::verify(&public_key, &digest, &signature) {
+        Ok(()) => println!("Valid: true"),
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
+fn log_der_decoding(signature: &str) {
+    let signature = match hex::decode(signature) {
+        Ok(signature) => signature,
+        Err(error) => return eprintln!("Error parsing signature: {:?}", error),
+    };
+
+    match DerSignature::decode(&signature) {
+        Ok(signature) => {
+            println!("Valid: true");
+            println!("R: {}", hex::encode(&signature.r));
+            println!("S: {}", hex::encode(&signature.s));
+            println!("Low S: {}", signature.is_low_s());
+
+            match signature.sighash_type {
+                Some(sighash_type) => println!(
+                    "Sighash type: {:02x} ({})",
+                    sighash_type,
+                    sighash_type_name(sighash_type).unwrap_or_else(|| "unknown".to_string())
+                ),
+                None => println!("Sighash type: none"),
+            }
+        }
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
+fn log_der_encoding(r: &str, s: &str, sighash_type: Option<&str>) {
+    let integer = |hex: &str| -> Result<Vec<u8>, String> {
+        let bytes = hex::decode(hex).map_err(|error| format!("{:?}", error))?;
+
+        match bytes.len() {
+            1..=32 => Ok([vec![0x00; 32 - bytes.len()], bytes].concat()),
+            length => Err(format!("expected 1 to 32 bytes, found {}", length)),
+        }
+    };
+
+    let r = integer(r).and_then(|r| {
+        let sighash_type = match sighash_type {
+            Some(sighash_type) => match hex::decode(sighash_type).map_err(|error| format!("{:?}", error))?[..] {
+                [byte] => Some(byte),
+                _ => return Err("expected a single sighash byte".to_string()),
+            },
+            None => None,
+        };
+
+        Ok(DerSignature { r, s: integer(s)?, sighash_type })
+    });
+
+    match r {
+        Ok(signature) => println!("Signature: {}", hex::encode(signature.encode())),
+        Err(error) => eprintln!("Error parsing input: {}", error),
+    }
+}
+
+fn log_decoded_transaction(transaction: &str, network: Network) {
+    let tx = match transaction.parse::<Transaction>() {
+        Ok(tx) => tx,
+        Err(error) => return eprintln!("Error decoding transaction: {:?}", error),
+    };
+
+    println!("Txid: {}", display_txid(&tx.txid()));
+    println!("Wtxid: {}", display_txid(&tx.wtxid()));
+    println!("Version: {}", tx.version);
+    println!("Size: {}", tx.size());
+    println!("Virtual size: {}", tx.vsize());
+    println!("Weight: {}", tx.weight());
+    println!("Locktime: {}", tx.lock_time);
+
+    for (index, input) in tx.inputs.iter().enumerate() {
+        println!("Input {}:", index);
+
+        match input.is_coinbase() {
+            true => println!("  Coinbase: {}", hex::encode(&input.script_sig)),
+            false => {
+                println!("  Outpoint: {}:{}", display_txid(&input.txid), input.vout);
+                println!("  ScriptSig (asm): {}", Script::new(input.script_sig.clone()).to_asm());
+            }
+        }
+
+        println!("  Sequence: 0x{:08x}", input.sequence);
+
+        if !input.witness.is_empty() {
+            let witness: Vec<String> = input.witness.iter().map(hex::encode).collect();
+            println!("  Witness: {}", witness.join(" "));
+        }
+    }
+
+    for (index, output) in tx.outputs.iter().enumerate() {
+        let script_pubkey = Script::new(output.script_pubkey.clone());
+
+        println!("Output {}:", index);
+        println!("  Value: {}", output.value);
+        println!("  ScriptPubKey: {}", script_pubkey.as_hex_string());
+        println!("  ScriptPubKey (asm): {}", script_pubkey.to_asm());
+
+        if let Some(address) = Address::from_script_pubkey(&output.script_pubkey, network) {
+            println!("  Address: {}", address);
+        }
+    }
+}
+
+fn log_signed_transaction(utxos: &[String], destinations: &[String], keys: &[String]) {
+    let utxos = match utxos.iter().map(|utxo| utxo.parse::<Utxo>()).collect::<Result<Vec<Utxo>, _>>() {
+        Ok(utxos) => utxos,
+        Err(error) => return eprintln!("Error parsing UTXO: {:?}", error),
+    };
+
+    let destinations = destinations.iter().map(|destination| {
+        let (address, amount) = destination
+            .rsplit_once(':')
+            .ok_or(format!("expected address:amount, found {}", destination))?;
+        let address = address.parse::<Address>().map_err(|error| format!("{:?}", error))?;
+        let amount = amount.parse::<u64>().map_err(|error| format!("{:?}", error))?;
+
+        if amount > MAX_MONEY {
+            return Err(format!("amount above {} satoshis, found {}", MAX_MONEY, amount));
+        }
+
+        Ok((address, amount))
+    });
+
+    let destinations = match destinations.collect::<Result<Vec<(Address, u64)>, String>>() {
+        Ok(destinations) => destinations,
+        Err(error) => return eprintln!("Error parsing destination: {}", error),
+    };
+
+    let keys = keys.iter().map(|key| PrivateKey::from_hex_or_wif(key));
+
+    let keys = match keys.collect::<Result<Vec<PrivateKey>, _>>() {
+        Ok(keys) => keys,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    let r = build_transaction(&utxos, &destinations).and_then(|mut tx| {
+        sign_transaction(&mut tx, &utxos, &keys)?;
+
+        let inputs = total_amount(utxos.iter().map(|utxo| utxo.value))?;
+        let outputs = total_amount(tx.outputs.iter().map(|output| output.value))?;
+
+        Ok((tx, inputs - outputs))
+    });
+
+    match r {
+        Ok((tx, fee)) => {
+            println!("Txid: {}", display_txid(&tx.txid()));
+            println!("Fee: {}", fee);
+            println!("Virtual size: {}", tx.vsize());
+            println!("Transaction: {}", hex::encode(tx.serialize()));
+        }
+        Err(error) => eprintln!("Error signing transaction: {:?}", error),
+    }
+}
+
+fn log_address_validation(address: &str) {
+    let r = address.parse::<Address>();
+
+    match r {
+        Ok(address) => {
+            let network = match (address.network, address.witness_version) {
+                (Network::Testnet, None) => "testnet/signet/regtest".to_string(),
+                (Network::Testnet, Some(_)) => "testnet/signet".to_string(),
+                (network, _) => network.to_string(),
+            };
+
+            println!("Valid: true");
+            println!("Network: {}", network);
+            println!("Type: {}", address.address_type);
+
+            match address.witness_version {
+                Some(version) => {
+                    println!("Witness version: {}", version);
+                    println!("Witness program: {}", hex::encode(&address.payload));
+                }
+                None => println!("Hash160: {}", hex::encode(&address.payload)),
+            }
+
+            let script_pubkey = Script::new(address.script_pubkey());
+
+            println!("ScriptPubKey: {}", script_pubkey.as_hex_string());
+            println!("ScriptPubKey (asm): {}", script_pubkey.to_asm());
+        }
+        Err(error) => {
+            println!("Valid: false");
+            println!("Error: {:?}", error);
+        }
+    }
+}
+
 fn log_base58_decoded(encoded: &str) {
-    let r = base58decode(encoded);
+    let r = base58decode_unchecked(encoded);
 
     match r {
         Ok(decoded) => {
-            println!("Version: {}", decoded.0);
-            println!("Payload: {}", decoded.1);
-            println!("Checksum: {}", decoded.2);
+            println!("Version: {}", hex::encode([decoded.version]));
+            println!("Payload: {}", hex::encode(&decoded.payload));
+            println!("Checksum: {}", hex::encode(&decoded.checksum));
+
+            match decoded.has_valid_checksum() {
+                true => println!("Checksum valid: true"),
+                false => println!(
+                    "Checksum valid: false (expected {})",
+                    hex::encode(decoded.expected_checksum())
+                ),
+            }
+
+            log_payload(&decoded.identify());
         },
         Err(error) => {
             eprintln!("Error decoding input: {:?}", error);
         }
     }
 }
+
+fn log_payload(payload: &Payload) {
+    println!("Type: {}", payload);
+
+    match payload {
+        Payload::P2pkh { hash160, .. } | Payload::P2sh { hash160, .. } => {
+            println!("Hash160: {}", hex::encode(hash160));
+        }
+        Payload::Wif { key, .. } => println!("Private key: {}", hex::encode(key)),
+        Payload::ExtendedKey { depth, fingerprint, child_number, chain_code, key, .. } => {
+            let index = match *child_number >= 0x80000000 {
+                true => format!("{}'", child_number - 0x80000000),
+                false => format!("{}", child_number),
+            };
+
+            println!("Depth: {}", depth);
+            println!("Parent fingerprint: {}", hex::encode(fingerprint));
+            println!("Child number: {} ({})", child_number, index);
+            println!("Chain code: {}", hex::encode(chain_code));
+            println!("Key: {}", hex::encode(key));
+        }
+        Payload::Unknown => (),
+    }
+}
+
+fn log_bech32_encoded(data: &str, hrp: &str, witness_version: Option<u8>, bech32m: bool) {
+    let data = match hex::decode(data) {
+        Ok(data) => data,
+        Err(error) => return eprintln!("Error decoding hex input: {:?}", error),
+    };
+
+    let variant = match bech32m {
+        true => Variant::Bech32m,
+        false => Variant::Bech32,
+    };
+
+    let r = match witness_version {
+        Some(version) => bech32::encode_segwit_address(hrp, version, &data),
+        None => bech32::convert_bits(&data, 8, 5, true)
+            .and_then(|data| bech32::encode(hrp, &data, variant)),
+    };
+
+    match r {
+        Ok(encoded) => println!("{}", encoded),
+        Err(error) => eprintln!("Error encoding input: {:?}", error),
+    }
+}
+
+fn log_bech32_decoded(encoded: &str) {
+    let r = bech32::decode(encoded);
+
+    match r {
+        Ok((hrp, data, variant)) => {
+            println!("HRP: {}", hrp);
+            println!("Variant: {:?}", variant);
+
+            match bech32::decode_segwit_address(encoded) {
+                Ok((_, version, program)) => {
+                    println!("Witness version: {}", version);
+                    println!("Witness program: {}", hex::encode(program));
+                }
+                Err(_) => match bech32::convert_bits(&data, 5, 8, false) {
+                    Ok(bytes) => println!("Data: {}", hex::encode(bytes)),
+                    Err(_) => println!("Data (5-bit): {:?}", data),
+                },
+            }
+        },
+        Err(error) => {
+            eprintln!("Error decoding input: {:?}", error);
+        }
+    }
+}
+
+fn log_script_assembled(asm: &str) {
+    let r = Script::from_asm(asm);
+
+    match r {
+        Ok(script) => println!("{}", script.as_hex_string()),
+        Err(error) => eprintln!("Error assembling script: {:?}", error),
+    }
+}
+
+fn log_script_disassembled(script: &str) {
+    let r = Script::from_hex(script);
+
+    match r {
+        Ok(script) => println!("{}", script.to_asm()),
+        Err(error) => eprintln!("Error decoding script: {:?}", error),
+    }
+}
@@ -1,5 +1,8 @@
 mod to_byte_array;
 pub use to_byte_array::ToByteArray;
 
+mod compact_size;
+pub use compact_size::compact_size;
+
 mod cli;
 pub use cli::run;
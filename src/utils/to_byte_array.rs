@@ -4,7 +4,7 @@ pub trait ToByteArray {
 
 impl ToByteArray for String {
     fn to_byte_array(self) -> Result<Vec<u8>, hex::FromHexError> {
-        match self.len() % 2 == 0 {
+        match self.len().is_multiple_of(2) {
             true => Ok(hex::decode(self)?),
             false => Ok(hex::decode(format!(
                 "{:0>width$}",